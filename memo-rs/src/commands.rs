@@ -0,0 +1,284 @@
+//! The non-interactive commands behind the `memo-rs` subcommands. Each one
+//! works on an already loaded [`Memos`] and writes its report to `out`;
+//! saving the store is left to the caller.
+
+use chrono::Local;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::process;
+
+use crate::config::{Config, OutputFormat};
+use crate::error::{Error, Result};
+use crate::import::{self, Format};
+use crate::memo::Memos;
+use crate::query::Query;
+use crate::render;
+use crate::stats::{Stats, MAX_WEEKS};
+use crate::watch::StoreWatcher;
+
+/// Prints the statistics report with `weeks` weeks of activity, 1 to
+/// [`MAX_WEEKS`].
+pub fn stats(memos: &Memos, weeks: u32, config: &Config, out: &mut impl Write) -> Result<()> {
+    if !(1..=MAX_WEEKS).contains(&weeks) {
+        return Err(Error::Usage(format!(
+            "weeks must be between 1 and {}",
+            MAX_WEEKS
+        )));
+    }
+    let stats = Stats::collect(memos, weeks, Local::now().date_naive());
+    match config.format {
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&stats)?)?,
+        OutputFormat::Text => write!(out, "{}", stats.to_text(&config.date_format))?,
+    }
+    Ok(())
+}
+
+/// Lists the memos in the configured sort order. Archived memos are left
+/// out unless `archived` is set.
+pub fn list(memos: &Memos, archived: bool, config: &Config, out: &mut impl Write) -> Result<()> {
+    let mut list = memos.get_all();
+    list.retain(|memo| archived || !memo.archived);
+    config.sort.sort(&mut list);
+    match config.format {
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&list)?)?,
+        OutputFormat::Text => {
+            for memo in list {
+                writeln!(
+                    out,
+                    "{}  {}",
+                    memo.updated_at.format(&config.date_format),
+                    memo.title
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints one memo, rendered as Markdown `width` columns wide or as JSON.
+pub fn show(
+    memos: &Memos,
+    title: &str,
+    config: &Config,
+    width: usize,
+    color: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let memo = memos
+        .get_one(title)
+        .ok_or_else(|| Error::NotFound(title.to_owned()))?;
+    match config.format {
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(memo)?)?,
+        OutputFormat::Text => write!(
+            out,
+            "{}",
+            render::to_terminal(memo, width, color, &config.date_format)
+        )?,
+    }
+    Ok(())
+}
+
+/// Opens the text of a memo in the configured editor. Returns whether the
+/// text changed.
+pub fn edit(memos: &mut Memos, title: &str, config: &Config, out: &mut impl Write) -> Result<bool> {
+    let text = match memos.get_one(title) {
+        Some(memo) => memo.text.to_owned(),
+        None => return Err(Error::NotFound(title.to_owned())),
+    };
+
+    let edited = edit_text(&config.editor, &text)?;
+    let edited = edited.trim_end();
+    if edited == text {
+        writeln!(out, "No changes")?;
+        return Ok(false);
+    }
+
+    memos.update(title, edited)?;
+    writeln!(out, "Updated!")?;
+    Ok(true)
+}
+
+// 에디터 설정은 "code --wait"처럼 인자를 포함할 수 있다.
+fn edit_text(editor: &str, text: &str) -> Result<String> {
+    // 다른 사용자가 미리 만들어 둔 경로를 쓰지 않도록 새 파일을 만든다.
+    let mut file = tempfile::Builder::new()
+        .prefix("memo-rs-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+    let path = file.path();
+
+    let mut words = editor.split_whitespace();
+    let program = match words.next() {
+        Some(program) => program,
+        None => return Err(Error::Editor("no editor configured".to_owned())),
+    };
+    let status = process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status();
+
+    // 파일은 `file`이 버려질 때 지워진다.
+    match status {
+        Ok(status) if status.success() => Ok(fs::read_to_string(path)?),
+        Ok(status) => Err(Error::Editor(format!("{} exited with {}", program, status))),
+        Err(e) => Err(Error::Editor(format!("{}: {}", program, e))),
+    }
+}
+
+/// Imports the memos at `path` and prints what was created, skipped or
+/// conflicted. Nothing is added on a dry run. Returns how many memos were added.
+pub fn import(
+    memos: &mut Memos,
+    path: &Path,
+    format: Option<Format>,
+    dry_run: bool,
+    out: &mut impl Write,
+) -> Result<usize> {
+    let records = import::read(path, format)?;
+    let plan = import::Plan::new(memos, records);
+    plan.print(out)?;
+
+    if dry_run {
+        writeln!(out, "Dry run, nothing was imported")?;
+        return Ok(0);
+    }
+    plan.apply(memos)
+}
+
+/// Exports the memo titled `title`, or the whole collection with an index
+/// page, as HTML into `dir`.
+pub fn export_html(
+    memos: &Memos,
+    dir: &Path,
+    title: Option<&str>,
+    config: &Config,
+    out: &mut impl Write,
+) -> Result<()> {
+    match title {
+        Some(title) => {
+            let memo = memos
+                .get_one(title)
+                .ok_or_else(|| Error::NotFound(title.to_owned()))?;
+            fs::create_dir_all(dir)?;
+            let path = dir.join(format!("{}.html", render::file_name(&memo.title)));
+            fs::write(&path, render::to_html(memo, None, &config.date_format))?;
+            writeln!(out, "Exported {}", path.display())?;
+        }
+        None => {
+            let mut list = memos.get_all();
+            config.sort.sort(&mut list);
+            render::export_collection(&list, dir, &config.date_format)?;
+            writeln!(out, "Exported {} memos to {}", list.len(), dir.display())?;
+        }
+    }
+    Ok(())
+}
+
+/// Watches the store at `path` and writes one JSON line per change made by
+/// any process, until writing fails.
+pub fn watch(path: &Path, out: &mut impl Write) -> Result<()> {
+    let mut watcher = StoreWatcher::new(path)?;
+    loop {
+        for event in watcher.wait(None)? {
+            writeln!(out, "{}", serde_json::to_string(&event)?)?;
+        }
+        out.flush()?;
+    }
+}
+
+/// What a bulk command does to every selected memo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkAction {
+    Delete,
+    Tag(Vec<String>),
+    Untag(Vec<String>),
+    Archive,
+    Unarchive,
+    /// Move to a notebook, or out of any notebook with `None`
+    Move(Option<String>),
+}
+
+impl BulkAction {
+    fn describe(&self) -> String {
+        match self {
+            Self::Delete => "delete".to_owned(),
+            Self::Tag(tags) => format!("tag with {}", tags.join(", ")),
+            Self::Untag(tags) => format!("remove the tags {}", tags.join(", ")),
+            Self::Archive => "archive".to_owned(),
+            Self::Unarchive => "unarchive".to_owned(),
+            Self::Move(Some(notebook)) => format!("move to {}", notebook),
+            Self::Move(None) => "move out of their notebook".to_owned(),
+        }
+    }
+}
+
+/// Applies `action` to every memo selected by `query`. The selection is
+/// previewed first and, unless `yes` is set, has to be confirmed with `y` on
+/// `input`. Returns how many memos were changed.
+pub fn bulk(
+    memos: &mut Memos,
+    query: &Query,
+    action: &BulkAction,
+    yes: bool,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> Result<usize> {
+    let titles: Vec<String> = query
+        .select(memos)
+        .into_iter()
+        .map(|memo| memo.title.to_owned())
+        .collect();
+    if titles.is_empty() {
+        writeln!(out, "No memo matches the query")?;
+        return Ok(0);
+    }
+
+    for title in &titles {
+        writeln!(out, "  {}", title)?;
+    }
+    writeln!(
+        out,
+        "{} memos will be affected: {}",
+        titles.len(),
+        action.describe()
+    )?;
+
+    if !yes {
+        write!(out, "Continue? [y/N] ")?;
+        out.flush()?;
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            writeln!(out, "Cancelled")?;
+            return Ok(0);
+        }
+    }
+
+    for title in &titles {
+        match action {
+            BulkAction::Delete => {
+                memos.remove(title)?;
+            }
+            BulkAction::Tag(tags) => memos.modify(title, |memo| {
+                for tag in tags {
+                    if !memo.tags.contains(tag) {
+                        memo.tags.push(tag.to_owned());
+                    }
+                }
+            })?,
+            BulkAction::Untag(tags) => {
+                memos.modify(title, |memo| memo.tags.retain(|tag| !tags.contains(tag)))?
+            }
+            BulkAction::Archive => memos.modify(title, |memo| memo.archived = true)?,
+            BulkAction::Unarchive => memos.modify(title, |memo| memo.archived = false)?,
+            BulkAction::Move(notebook) => {
+                memos.modify(title, |memo| memo.notebook = notebook.clone())?
+            }
+        }
+    }
+    writeln!(out, "Done")?;
+    Ok(titles.len())
+}
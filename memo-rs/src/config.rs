@@ -0,0 +1,206 @@
+use chrono::format::{Item, StrftimeItems};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::memo::Memo;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read {0}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("invalid config {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("unknown profile: {0}")]
+    UnknownProfile(String),
+    #[error("profile {0:?} needs a store: its name isn't a valid file name")]
+    ProfileName(String),
+    #[error("invalid date format: {0}")]
+    DateFormat(String),
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Alphabetically by title
+    Title,
+    /// Oldest first
+    Created,
+    /// Most recently edited first
+    Updated,
+}
+
+impl SortOrder {
+    pub fn sort(self, memos: &mut [&Memo]) {
+        match self {
+            Self::Title => memos.sort_by(|a, b| a.title.cmp(&b.title)),
+            Self::Created => memos.sort_by_key(|memo| memo.created_at),
+            Self::Updated => memos.sort_by_key(|memo| std::cmp::Reverse(memo.updated_at)),
+        }
+    }
+}
+
+/// Settings that can be given at the top level of the config file or per profile.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Settings {
+    store: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    editor: Option<String>,
+    sort: Option<SortOrder>,
+    date_format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    /// Profile used when `--profile` is not given
+    profile: Option<String>,
+    #[serde(flatten)]
+    settings: Settings,
+    profiles: HashMap<String, Settings>,
+}
+
+/// Effective configuration after merging the selected profile over the
+/// top-level settings and the built-in defaults.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub store: PathBuf,
+    pub format: OutputFormat,
+    pub editor: String,
+    pub sort: SortOrder,
+    pub date_format: String,
+}
+
+pub fn default_config_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("memo-rs")
+        .join("config.toml")
+}
+
+fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("memo-rs")
+}
+
+// "~/"로 시작하는 경로는 홈 디렉터리 기준, 상대 경로는 설정 파일 기준으로 푼다.
+fn resolve(path: &Path, base: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    if path.is_relative() {
+        base.join(path)
+    } else {
+        path.to_owned()
+    }
+}
+
+fn default_editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_owned())
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            store: data_dir().join("memos.json"),
+            format: OutputFormat::Text,
+            editor: default_editor(),
+            sort: SortOrder::Title,
+            date_format: DEFAULT_DATE_FORMAT.to_owned(),
+        }
+    }
+}
+
+/// Letters, digits, `-` and `_` only.
+fn is_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl Config {
+    /// Reads the config file at `path` (or the default location) and applies
+    /// `profile`. A missing config file means all defaults.
+    pub fn load(path: Option<&Path>, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let path = path.map(Path::to_owned).unwrap_or_else(default_config_path);
+        let file: ConfigFile = match fs::read_to_string(&path) {
+            Ok(buffer) => {
+                toml::from_str(&buffer).map_err(|e| ConfigError::Parse(path.clone(), e))?
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => ConfigFile::default(),
+            Err(e) => return Err(ConfigError::Io(path, e)),
+        };
+        let base = path.parent().unwrap_or(Path::new("."));
+
+        let profile = profile.map(str::to_owned).or(file.profile);
+        let selected = match &profile {
+            Some(name) => match file.profiles.get(name) {
+                Some(settings) => Some(settings),
+                None => return Err(ConfigError::UnknownProfile(name.to_owned())),
+            },
+            None => None,
+        };
+        let top = &file.settings;
+
+        // 프로필마다 저장소를 따로 쓰도록, 저장소를 지정하지 않은 프로필은
+        // 프로필 이름으로 된 파일을 기본값으로 쓴다.
+        let store = match (selected.and_then(|s| s.store.as_ref()), &profile) {
+            (Some(store), _) => resolve(store, base),
+            // 이름이 경로가 되므로 데이터 디렉터리 밖을 가리키지 못하게 한다.
+            (None, Some(name)) if is_file_name(name) => data_dir().join(format!("{}.json", name)),
+            (None, Some(name)) => return Err(ConfigError::ProfileName(name.to_owned())),
+            (None, None) => match &top.store {
+                Some(store) => resolve(store, base),
+                None => data_dir().join("memos.json"),
+            },
+        };
+
+        let date_format = selected
+            .and_then(|s| s.date_format.clone())
+            .or_else(|| top.date_format.clone())
+            .unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_owned());
+        if StrftimeItems::new(&date_format).any(|item| item == Item::Error) {
+            return Err(ConfigError::DateFormat(date_format));
+        }
+
+        let editor = selected
+            .and_then(|s| s.editor.clone())
+            .or_else(|| top.editor.clone())
+            .unwrap_or_else(default_editor);
+
+        Ok(Self {
+            store,
+            format: selected
+                .and_then(|s| s.format)
+                .or(top.format)
+                .unwrap_or(OutputFormat::Text),
+            editor,
+            sort: selected
+                .and_then(|s| s.sort)
+                .or(top.sort)
+                .unwrap_or(SortOrder::Title),
+            date_format,
+        })
+    }
+}
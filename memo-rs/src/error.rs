@@ -0,0 +1,37 @@
+use std::io;
+use thiserror::Error;
+
+use crate::config::ConfigError;
+use crate::import::ImportError;
+use crate::query::QueryError;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("memo not found: {0}")]
+    NotFound(String),
+    #[error("memo already exists: {0}")]
+    AlreadyExists(String),
+    #[error("memo title is empty")]
+    EmptyTitle,
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid memo store: {0}")]
+    Store(#[from] serde_json::Error),
+    #[error(transparent)]
+    Import(#[from] ImportError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Query(#[from] QueryError),
+    #[error("cannot watch the store: {0}")]
+    Watch(#[from] notify::Error),
+    #[error("editor failed: {0}")]
+    Editor(String),
+    #[error("{0}")]
+    Usage(String),
+    #[error("cannot read the command line: {0}")]
+    Readline(#[from] rustyline::error::ReadlineError),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
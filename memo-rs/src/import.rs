@@ -0,0 +1,238 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::error;
+use crate::memo::{parse_tags, Memo, Memos};
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid csv: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("missing column: {0}")]
+    MissingColumn(String),
+    #[error("cannot guess the source format of {0}, use --from")]
+    UnknownFormat(String),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// A directory of .txt files
+    Txt,
+    Csv,
+    Json,
+}
+
+impl Format {
+    /// Guesses the format from the path: a directory is read as text files,
+    /// otherwise the extension decides.
+    pub fn guess(path: &Path) -> Option<Self> {
+        if path.is_dir() {
+            return Some(Self::Txt);
+        }
+        match path.extension()?.to_str()? {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the records at `path`, guessing the format when `format` is `None`.
+pub fn read(path: &Path, format: Option<Format>) -> Result<Vec<Record>, ImportError> {
+    let format = match format.or_else(|| Format::guess(path)) {
+        Some(format) => format,
+        None => return Err(ImportError::UnknownFormat(path.display().to_string())),
+    };
+
+    match format {
+        Format::Txt => from_text_dir(path),
+        Format::Csv => from_csv(path),
+        Format::Json => from_json(path),
+    }
+}
+
+/// A memo read from an external source, before it is checked against the store.
+#[derive(Debug, Deserialize)]
+pub struct Record {
+    pub title: String,
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notebook: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Created,
+    Skipped(String),
+    Conflicted,
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pub entries: Vec<(Record, Outcome)>,
+}
+
+impl Plan {
+    /// Decides what to do with every record without touching `memos`.
+    ///
+    /// A record whose title already exists is skipped when the text is the
+    /// same and reported as a conflict otherwise. Conflicts are never written.
+    pub fn new(memos: &Memos, records: Vec<Record>) -> Self {
+        let mut seen: HashMap<String, String> = HashMap::new();
+        let mut entries = vec![];
+
+        for record in records {
+            let outcome = if record.title.is_empty() {
+                Outcome::Skipped("empty title".to_owned())
+            } else if record.text.is_empty() {
+                Outcome::Skipped("empty text".to_owned())
+            } else {
+                let existing = seen
+                    .get(&record.title)
+                    .map(|text| text.as_str())
+                    .or_else(|| memos.get_one(&record.title).map(|memo| memo.text.as_str()));
+                match existing {
+                    Some(text) if text == record.text => {
+                        Outcome::Skipped("already exists".to_owned())
+                    }
+                    Some(_) => Outcome::Conflicted,
+                    None => {
+                        seen.insert(record.title.to_owned(), record.text.to_owned());
+                        Outcome::Created
+                    }
+                }
+            };
+            entries.push((record, outcome));
+        }
+
+        Self { entries }
+    }
+
+    pub fn print(&self, out: &mut impl Write) -> io::Result<()> {
+        let (mut created, mut skipped, mut conflicted) = (0, 0, 0);
+        for (record, outcome) in &self.entries {
+            match outcome {
+                Outcome::Created => {
+                    created += 1;
+                    writeln!(out, "create    {}", record.title)?;
+                }
+                Outcome::Skipped(reason) => {
+                    skipped += 1;
+                    writeln!(out, "skip      {} ({})", record.title, reason)?;
+                }
+                Outcome::Conflicted => {
+                    conflicted += 1;
+                    writeln!(out, "conflict  {}", record.title)?;
+                }
+            }
+        }
+        writeln!(
+            out,
+            "{} created, {} skipped, {} conflicted",
+            created, skipped, conflicted
+        )
+    }
+
+    /// Adds the memos planned as created and returns how many were added.
+    pub fn apply(self, memos: &mut Memos) -> error::Result<usize> {
+        let mut added = 0;
+        for (record, outcome) in self.entries {
+            if outcome == Outcome::Created {
+                let mut memo = Memo::new(record.title, record.text);
+                memo.tags = record.tags;
+                memo.notebook = record.notebook;
+                memos.add(memo)?;
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+}
+
+/// Reads every `.txt` file in `dir`, using the file name as the title.
+pub fn from_text_dir(dir: &Path) -> Result<Vec<Record>, ImportError> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+
+    let mut records = vec![];
+    for path in paths {
+        let title = match path.file_stem() {
+            Some(stem) => stem.to_string_lossy().trim().to_owned(),
+            None => continue,
+        };
+        let text = fs::read_to_string(&path)?.trim().to_owned();
+        records.push(Record {
+            title,
+            text,
+            tags: vec![],
+            notebook: None,
+        });
+    }
+    Ok(records)
+}
+
+/// Reads a CSV file with a header row. `title` and `text` columns are
+/// required, `tags` (comma separated) and `notebook` are optional.
+pub fn from_csv(path: &Path) -> Result<Vec<Record>, ImportError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|header| header.trim() == name);
+
+    let title = column("title").ok_or_else(|| ImportError::MissingColumn("title".to_owned()))?;
+    let text = column("text").ok_or_else(|| ImportError::MissingColumn("text".to_owned()))?;
+    let tags = column("tags");
+    let notebook = column("notebook");
+
+    let mut records = vec![];
+    for row in reader.records() {
+        let row = row?;
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| row.get(index))
+                .map(|value| value.trim().to_owned())
+                .filter(|value| !value.is_empty())
+        };
+        records.push(Record {
+            title: field(Some(title)).unwrap_or_default(),
+            text: field(Some(text)).unwrap_or_default(),
+            tags: field(tags)
+                .map(|value| parse_tags(&value))
+                .unwrap_or_default(),
+            notebook: field(notebook),
+        });
+    }
+    Ok(records)
+}
+
+/// Reads a JSON array of `{ "title", "text", "tags"?, "notebook"? }` objects.
+pub fn from_json(path: &Path) -> Result<Vec<Record>, ImportError> {
+    let buffer = fs::read_to_string(path)?;
+    let records: Vec<Record> = serde_json::from_str(&buffer)?;
+    // 다른 형식과 같이 앞뒤 공백과 빈 값을 버린다.
+    Ok(records
+        .into_iter()
+        .map(|record| Record {
+            title: record.title.trim().to_owned(),
+            text: record.text.trim().to_owned(),
+            tags: parse_tags(&record.tags.join(",")),
+            notebook: record
+                .notebook
+                .map(|notebook| notebook.trim().to_owned())
+                .filter(|notebook| !notebook.is_empty()),
+        })
+        .collect())
+}
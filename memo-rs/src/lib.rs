@@ -0,0 +1,35 @@
+//! A small memo manager.
+//!
+//! [`Memos`] is the collection of [`Memo`]s, stored as a JSON file. The
+//! [`commands`] module holds the non-interactive subcommands and [`Menu`]
+//! the numbered interactive menu, while [`repl::Repl`] is a command shell
+//! with history and completion; all of them write to any [`std::io::Write`] so
+//! they can be driven from tests.
+//!
+//! ```
+//! use memo_rs::{Memo, Memos};
+//!
+//! let mut memos = Memos::new();
+//! memos.add(Memo::new("groceries".to_owned(), "milk, eggs".to_owned())).unwrap();
+//! memos.update("groceries", "milk, eggs, bread").unwrap();
+//! assert_eq!(memos.get_one("groceries").unwrap().text, "milk, eggs, bread");
+//! assert!(memos.remove("unknown").is_err());
+//! ```
+
+pub mod commands;
+pub mod config;
+pub mod import;
+pub mod query;
+pub mod render;
+pub mod repl;
+pub mod stats;
+pub mod watch;
+
+mod error;
+mod memo;
+mod menu;
+
+pub use config::Config;
+pub use error::{Error, Result};
+pub use memo::{parse_tags, Memo, Memos};
+pub use menu::Menu;
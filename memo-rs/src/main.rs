@@ -1,187 +1,282 @@
-use std::collections::HashMap;
-use std::io;
-
-#[derive(Debug, Clone)]
-struct Memo {
-    title: String,
-    text: String,
-}
-
-struct Memos {
-    inner: HashMap<String, Memo>,
+use chrono::Local;
+use clap::{Args, Parser, Subcommand};
+use memo_rs::commands::{self, BulkAction};
+use memo_rs::config::{Config, OutputFormat, SortOrder};
+use memo_rs::import::Format;
+use memo_rs::query::Query;
+use memo_rs::repl::Repl;
+use memo_rs::stats;
+use memo_rs::{parse_tags, Memos, Menu};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Manage memos")]
+struct Cli {
+    /// Path of the memo store (overrides the config file)
+    #[arg(long, global = true)]
+    store: Option<PathBuf>,
+    /// Path of the config file (defaults to the user config directory)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Named profile from the config file
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    #[command(subcommand)]
+    cmd: Option<Command>,
 }
 
-impl Memos {
-    fn new() -> Self {
-        Self {
-            inner: HashMap::new(),
-        }
-    }
-
-    fn add(&mut self, memo: Memo) {
-        self.inner.insert(memo.title.clone(), memo);
-    }
-
-    fn get_all(&self) -> Vec<&Memo> {
-        let mut memos = vec![];
-        for memo in self.inner.values() {
-            memos.push(memo);
-        }
-        memos
-    }
-
-    fn get_one(&self, title: &str) -> Option<&Memo> {
-        self.inner.get(title)
-    }
-
-    fn remove(&mut self, title: &str) -> bool {
-        self.inner.remove(title).is_some()
-    }
-
-    fn update(&mut self, title: &str, text: &str) -> bool {
-        match self.inner.get_mut(title) {
-            Some(memo) => {
-                memo.text = text.to_owned();
-                true
-            }
-            None => false,
-        }
-    }
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print statistics and recent activity
+    Stats {
+        /// Number of weeks covered by the activity histogram, at most 520
+        #[arg(short, long, default_value_t = 4, value_parser = weeks_range())]
+        weeks: u32,
+        /// Output format (defaults to the config file)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+    /// List memos in the configured sort order
+    List {
+        /// Output format (defaults to the config file)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+        /// Sort order (defaults to the config file)
+        #[arg(long, value_enum)]
+        sort: Option<SortOrder>,
+        /// Include archived memos
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Import memos from a directory of .txt files, a CSV file or a JSON array
+    Import {
+        path: PathBuf,
+        /// Source format (guessed from the path when omitted)
+        #[arg(long, value_enum)]
+        from: Option<Format>,
+        /// Only show what would be created, skipped or conflicted
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Render a memo as Markdown in the terminal
+    Show {
+        title: String,
+        /// Output format (defaults to the config file)
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+    /// Edit the text of a memo in the configured editor
+    Edit { title: String },
+    /// Stream memo changes made by any process as JSON lines
+    Watch,
+    /// Export memos as static HTML pages with an index page
+    ExportHtml {
+        /// Output directory
+        out: PathBuf,
+        /// Export only this memo
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Start a command shell with history and tab completion
+    Repl,
+    /// Delete, tag, archive or move every memo matching a query
+    Bulk {
+        #[command(subcommand)]
+        cmd: BulkCommand,
+    },
 }
 
-fn get_input() -> Option<String> {
-    let mut buffer = String::new();
-
-    while io::stdin().read_line(&mut buffer).is_err() {
-        println!("Please enter the your data again")
-    }
-
-    let input = buffer.trim().to_owned();
-
-    if &input == "" {
-        None
-    } else {
-        Some(input)
-    }
+#[derive(Subcommand, Debug)]
+enum BulkCommand {
+    /// Delete the selected memos
+    Delete {
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// Add tags to the selected memos
+    Tag {
+        /// Comma separated tags
+        tags: String,
+        /// Remove the tags instead
+        #[arg(long)]
+        remove: bool,
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// Archive the selected memos
+    Archive {
+        /// Unarchive instead
+        #[arg(long)]
+        undo: bool,
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// Move the selected memos to a notebook
+    Move {
+        notebook: String,
+        #[command(flatten)]
+        selection: Selection,
+    },
 }
 
-fn add_memo(memos: &mut Memos) {
-    println!("Memo title:");
-
-    let title = match get_input() {
-        Some(input) => input,
-        None => return,
-    };
-
-    println!("Memo text:");
-
-    let text = match get_input() {
-        Some(input) => input,
-        None => return,
-    };
-
-    let memo = Memo { title, text };
-    memos.add(memo);
-    println!("Memo Added")
+#[derive(Args, Debug)]
+struct Selection {
+    /// Glob on the title, e.g. "meeting-*"
+    #[arg(long)]
+    title: Option<String>,
+    /// Memos with this tag
+    #[arg(long)]
+    tag: Option<String>,
+    /// Last edited before a date (YYYY-MM-DD) or a duration ago (30d, 4w)
+    #[arg(long)]
+    older_than: Option<String>,
+    /// Regex searched in the memo text
+    #[arg(long)]
+    text: Option<String>,
+    /// Don't ask for confirmation
+    #[arg(short, long)]
+    yes: bool,
 }
 
-fn remove_memo(memos: &mut Memos) {
-    for memo in memos.get_all() {
-        println!("{:?}", memo);
+impl BulkCommand {
+    fn into_parts(self) -> (BulkAction, Selection) {
+        match self {
+            Self::Delete { selection } => (BulkAction::Delete, selection),
+            Self::Tag {
+                tags,
+                remove: false,
+                selection,
+            } => (BulkAction::Tag(parse_tags(&tags)), selection),
+            Self::Tag {
+                tags,
+                remove: true,
+                selection,
+            } => (BulkAction::Untag(parse_tags(&tags)), selection),
+            Self::Archive {
+                undo: false,
+                selection,
+            } => (BulkAction::Archive, selection),
+            Self::Archive {
+                undo: true,
+                selection,
+            } => (BulkAction::Unarchive, selection),
+            Self::Move {
+                notebook,
+                selection,
+            } => (BulkAction::Move(Some(notebook)), selection),
+        }
     }
+}
 
-    println!("Enter the memo title to remove:");
-
-    let title = match get_input() {
-        Some(input) => input,
-        None => return,
-    };
-
-    if memos.remove(&title) {
-        println!("Removed!");
-    } else {
-        println!("Not found memo");
+fn terminal_width() -> usize {
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(width), _)) => width as usize,
+        None => 80,
     }
 }
 
-fn update_memo(memos: &mut Memos) {
-    for memo in memos.get_all() {
-        println!("{:?}", memo);
+fn run(cli: Cli) -> memo_rs::Result<()> {
+    let mut config = Config::load(cli.config.as_deref(), cli.profile.as_deref())?;
+    if let Some(store) = cli.store {
+        config.store = store;
     }
+    let store = config.store.clone();
+    let mut out = io::stdout().lock();
 
-    println!("Please enter the title to update:");
-    let title = match get_input() {
-        Some(input) => input,
-        None => return,
-    };
-
-    match memos.get_one(&title) {
-        Some(_) => (),
+    let cmd = match cli.cmd {
+        Some(cmd) => cmd,
         None => {
-            println!("Not found memo");
-            return;
+            let mut menu = Menu::new(io::stdin().lock(), out);
+            menu.width = terminal_width();
+            menu.color = io::stdout().is_terminal();
+            return menu.run(&config);
         }
-    }
-
-    println!("Please enter the text");
-    let text = match get_input() {
-        Some(input) => input,
-        None => return,
     };
 
-    if memos.update(&title, &text) {
-        println!("Updated!");
-    } else {
-        println!("Not found memo")
-    }
-}
-
-fn show_memos(memos: &Memos) {
-    for memo in memos.get_all() {
-        println!("{:?}", memo);
-    }
-}
-
-fn display_menu() {
-    fn show() {
-        println!("");
-        println!("== Manage Memos ==");
-        println!("1. Add memo");
-        println!("2. View memos");
-        println!("3. Remove memo");
-        println!("4. Update memo");
-        println!("q. quit");
-        println!("");
-        println!("Enter selection:");
-    }
-
-    let mut memos = Memos::new();
-
-    loop {
-        show();
-        let input = match get_input() {
-            Some(input) => input,
-            None => return,
-        };
-
-        if input == "q" {
-            println!("GoodBye");
-            return;
+    match cmd {
+        Command::Stats { weeks, format } => {
+            config.format = format.unwrap_or(config.format);
+            commands::stats(&Memos::load(&store)?, weeks, &config, &mut out)?;
         }
-
-        match input.as_str() {
-            "1" => add_memo(&mut memos),
-            "2" => show_memos(&memos),
-            "3" => remove_memo(&mut memos),
-            "4" => update_memo(&mut memos),
-            "q" => break,
-            _ => {
-                println!("Invalid command")
+        Command::List {
+            format,
+            sort,
+            archived,
+        } => {
+            config.format = format.unwrap_or(config.format);
+            config.sort = sort.unwrap_or(config.sort);
+            commands::list(&Memos::load(&store)?, archived, &config, &mut out)?;
+        }
+        Command::Import {
+            path,
+            from,
+            dry_run,
+        } => {
+            let mut memos = Memos::load(&store)?;
+            if commands::import(&mut memos, &path, from, dry_run, &mut out)? > 0 {
+                memos.save(&store)?;
+            }
+        }
+        Command::Show { title, format } => {
+            config.format = format.unwrap_or(config.format);
+            let color = io::stdout().is_terminal();
+            let memos = Memos::load(&store)?;
+            commands::show(&memos, &title, &config, terminal_width(), color, &mut out)?;
+        }
+        Command::Edit { title } => {
+            let mut memos = Memos::load(&store)?;
+            if commands::edit(&mut memos, &title, &config, &mut out)? {
+                memos.save(&store)?;
+            }
+        }
+        Command::Watch => commands::watch(&store, &mut out)?,
+        Command::ExportHtml { out: dir, title } => {
+            let memos = Memos::load(&store)?;
+            commands::export_html(&memos, &dir, title.as_deref(), &config, &mut out)?;
+        }
+        Command::Repl => {
+            drop(out);
+            let mut repl = Repl::new(&config)?;
+            repl.width = terminal_width();
+            repl.color = io::stdout().is_terminal();
+            repl.run()?;
+            return Ok(());
+        }
+        Command::Bulk { cmd } => {
+            let (action, selection) = cmd.into_parts();
+            let query = Query::parse(
+                selection.title.as_deref(),
+                selection.tag.as_deref(),
+                selection.older_than.as_deref(),
+                selection.text.as_deref(),
+                Local::now(),
+            )?;
+            let mut memos = Memos::load(&store)?;
+            let mut input = io::stdin().lock();
+            if commands::bulk(
+                &mut memos,
+                &query,
+                &action,
+                selection.yes,
+                &mut input,
+                &mut out,
+            )? > 0
+            {
+                memos.save(&store)?;
             }
         }
     }
+    Ok(())
+}
+
+fn weeks_range() -> clap::builder::RangedI64ValueParser<u32> {
+    clap::value_parser!(u32).range(1..=i64::from(stats::MAX_WEEKS))
 }
 
 fn main() {
-    display_menu();
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("memo-rs: {}", e);
+        std::process::exit(1);
+    }
 }
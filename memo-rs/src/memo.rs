@@ -0,0 +1,183 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::error::{Error, Result};
+use crate::watch::{ChangeEvent, ChangeKind};
+
+/// A single note. The title is unique within a [`Memos`] collection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Memo {
+    pub title: String,
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notebook: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl Memo {
+    /// Creates an untagged memo outside of any notebook, stamped with the current time.
+    pub fn new(title: String, text: String) -> Self {
+        let now = Local::now();
+        Self {
+            title,
+            text,
+            tags: vec![],
+            notebook: None,
+            archived: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Splits a comma separated list of tags, dropping empty entries.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|tag| tag.trim().to_owned())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// The memo collection, keyed by title.
+#[derive(Debug, Default)]
+pub struct Memos {
+    inner: HashMap<String, Memo>,
+    subscribers: Vec<Sender<ChangeEvent>>,
+}
+
+impl Memos {
+    pub fn new() -> Self {
+        Self {
+            inner: HashMap::new(),
+            subscribers: vec![],
+        }
+    }
+
+    /// Returns a receiver for every later add, update and remove on this
+    /// collection. Dropping the receiver ends the subscription.
+    pub fn subscribe(&mut self) -> Receiver<ChangeEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.push(tx);
+        rx
+    }
+
+    fn notify(&mut self, kind: ChangeKind, title: &str) {
+        let event = ChangeEvent::new(kind, title);
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /// Reads a JSON memo store. A store that doesn't exist yet is an empty collection.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut memos = Self::new();
+        let buffer = match fs::read_to_string(path) {
+            Ok(buffer) => buffer,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(memos),
+            Err(e) => return Err(e.into()),
+        };
+
+        let list: Vec<Memo> = serde_json::from_str(&buffer)?;
+        for memo in list {
+            memos.inner.insert(memo.title.clone(), memo);
+        }
+        Ok(memos)
+    }
+
+    /// Writes the collection to `path`, creating the parent directory if needed.
+    ///
+    /// The store is replaced in one step, so other processes never read a
+    /// half-written file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut list = self.get_all();
+        list.sort_by(|a, b| a.title.cmp(&b.title));
+        let buffer = serde_json::to_string_pretty(&list)?;
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.tmp", std::process::id()));
+        fs::write(&tmp, buffer)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Adds a new memo. Fails if the title is empty or already taken.
+    pub fn add(&mut self, memo: Memo) -> Result<()> {
+        if memo.title.is_empty() {
+            return Err(Error::EmptyTitle);
+        }
+        if self.inner.contains_key(&memo.title) {
+            return Err(Error::AlreadyExists(memo.title));
+        }
+        let title = memo.title.clone();
+        self.inner.insert(title.clone(), memo);
+        self.notify(ChangeKind::Added, &title);
+        Ok(())
+    }
+
+    /// Returns every memo in no particular order.
+    pub fn get_all(&self) -> Vec<&Memo> {
+        let mut memos = vec![];
+        for memo in self.inner.values() {
+            memos.push(memo);
+        }
+        memos
+    }
+
+    pub fn get_one(&self, title: &str) -> Option<&Memo> {
+        self.inner.get(title)
+    }
+
+    /// Removes the memo titled `title` and returns it.
+    pub fn remove(&mut self, title: &str) -> Result<Memo> {
+        let memo = self
+            .inner
+            .remove(title)
+            .ok_or_else(|| Error::NotFound(title.to_owned()))?;
+        self.notify(ChangeKind::Removed, title);
+        Ok(memo)
+    }
+
+    /// Replaces the text of the memo titled `title` and bumps its edit time.
+    pub fn update(&mut self, title: &str, text: &str) -> Result<()> {
+        self.modify(title, |memo| memo.text = text.to_owned())
+    }
+
+    /// Changes the memo titled `title` with `f` and bumps its edit time.
+    /// The title is the key, so a title changed by `f` is put back.
+    pub fn modify(&mut self, title: &str, f: impl FnOnce(&mut Memo)) -> Result<()> {
+        match self.inner.get_mut(title) {
+            Some(memo) => {
+                f(memo);
+                if memo.title != title {
+                    memo.title = title.to_owned();
+                }
+                memo.updated_at = Local::now();
+                self.notify(ChangeKind::Updated, title);
+                Ok(())
+            }
+            None => Err(Error::NotFound(title.to_owned())),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
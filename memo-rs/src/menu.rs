@@ -0,0 +1,252 @@
+use chrono::Local;
+use std::io::{BufRead, Write};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::memo::{parse_tags, Memo, Memos};
+use crate::render;
+use crate::stats::{Stats, MAX_WEEKS};
+
+/// The numbered interactive menu, reading choices from `input` and writing
+/// prompts to `output`.
+pub struct Menu<R, W> {
+    input: R,
+    output: W,
+    /// Width used when rendering a memo
+    pub width: usize,
+    /// Whether rendered memos use ANSI styles
+    pub color: bool,
+}
+
+impl<R: BufRead, W: Write> Menu<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            width: 80,
+            color: false,
+        }
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Reads one line. An empty line or the end of the input gives `None`.
+    fn get_input(&mut self) -> Result<Option<String>> {
+        let mut buffer = String::new();
+        self.input.read_line(&mut buffer)?;
+
+        let input = buffer.trim().to_owned();
+
+        if input.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(input))
+        }
+    }
+
+    fn show(&mut self) -> Result<()> {
+        writeln!(self.output)?;
+        writeln!(self.output, "== Manage Memos ==")?;
+        writeln!(self.output, "1. Add memo")?;
+        writeln!(self.output, "2. View memos")?;
+        writeln!(self.output, "3. Remove memo")?;
+        writeln!(self.output, "4. Update memo")?;
+        writeln!(self.output, "5. Statistics")?;
+        writeln!(self.output, "6. Show memo")?;
+        writeln!(self.output, "q. quit")?;
+        writeln!(self.output)?;
+        writeln!(self.output, "Enter selection:")?;
+        Ok(())
+    }
+
+    /// Runs the menu on the store of `config` until `q` or an empty
+    /// selection, saving after every command that changed a memo.
+    pub fn run(&mut self, config: &Config) -> Result<()> {
+        let mut memos = Memos::load(&config.store)?;
+
+        loop {
+            self.show()?;
+            let input = match self.get_input()? {
+                Some(input) => input,
+                None => return Ok(()),
+            };
+
+            // 다른 프로세스가 저장한 메모를 덮어쓰지 않도록 바뀐 때만 저장한다.
+            let changed = match input.as_str() {
+                "1" => self.add_memo(&mut memos)?,
+                "2" => {
+                    self.show_memos(&memos, config)?;
+                    false
+                }
+                "3" => self.remove_memo(&mut memos, config)?,
+                "4" => self.update_memo(&mut memos, config)?,
+                "5" => {
+                    self.show_stats(&memos, config)?;
+                    false
+                }
+                "6" => {
+                    self.show_memo(&memos, config)?;
+                    false
+                }
+                "q" => {
+                    writeln!(self.output, "GoodBye")?;
+                    return Ok(());
+                }
+                _ => {
+                    writeln!(self.output, "Invalid command")?;
+                    false
+                }
+            };
+
+            if changed {
+                memos.save(&config.store)?;
+            }
+        }
+    }
+
+    /// Returns whether a memo was changed.
+    pub fn add_memo(&mut self, memos: &mut Memos) -> Result<bool> {
+        writeln!(self.output, "Memo title:")?;
+
+        let title = match self.get_input()? {
+            Some(input) => input,
+            None => return Ok(false),
+        };
+
+        writeln!(self.output, "Memo text:")?;
+
+        let text = match self.get_input()? {
+            Some(input) => input,
+            None => return Ok(false),
+        };
+
+        writeln!(self.output, "Memo tags (comma separated, optional):")?;
+        let tags = self
+            .get_input()?
+            .map(|input| parse_tags(&input))
+            .unwrap_or_default();
+
+        writeln!(self.output, "Memo notebook (optional):")?;
+        let notebook = self.get_input()?;
+
+        let mut memo = Memo::new(title, text);
+        memo.tags = tags;
+        memo.notebook = notebook;
+        match memos.add(memo) {
+            Ok(()) => {
+                writeln!(self.output, "Memo Added")?;
+                return Ok(true);
+            }
+            Err(Error::AlreadyExists(_)) => writeln!(self.output, "Memo already exists")?,
+            Err(e) => return Err(e),
+        }
+        Ok(false)
+    }
+
+    /// Returns whether a memo was changed.
+    pub fn remove_memo(&mut self, memos: &mut Memos, config: &Config) -> Result<bool> {
+        self.show_memos(memos, config)?;
+
+        writeln!(self.output, "Enter the memo title to remove:")?;
+
+        let title = match self.get_input()? {
+            Some(input) => input,
+            None => return Ok(false),
+        };
+
+        match memos.remove(&title) {
+            Ok(_) => {
+                writeln!(self.output, "Removed!")?;
+                return Ok(true);
+            }
+            Err(Error::NotFound(_)) => writeln!(self.output, "Not found memo")?,
+            Err(e) => return Err(e),
+        }
+        Ok(false)
+    }
+
+    /// Returns whether a memo was changed.
+    pub fn update_memo(&mut self, memos: &mut Memos, config: &Config) -> Result<bool> {
+        self.show_memos(memos, config)?;
+
+        writeln!(self.output, "Please enter the title to update:")?;
+        let title = match self.get_input()? {
+            Some(input) => input,
+            None => return Ok(false),
+        };
+
+        if memos.get_one(&title).is_none() {
+            writeln!(self.output, "Not found memo")?;
+            return Ok(false);
+        }
+
+        writeln!(self.output, "Please enter the text")?;
+        let text = match self.get_input()? {
+            Some(input) => input,
+            None => return Ok(false),
+        };
+
+        match memos.update(&title, &text) {
+            Ok(()) => {
+                writeln!(self.output, "Updated!")?;
+                return Ok(true);
+            }
+            Err(Error::NotFound(_)) => writeln!(self.output, "Not found memo")?,
+            Err(e) => return Err(e),
+        }
+        Ok(false)
+    }
+
+    pub fn show_memos(&mut self, memos: &Memos, config: &Config) -> Result<()> {
+        let mut list = memos.get_all();
+        config.sort.sort(&mut list);
+        for memo in list {
+            writeln!(self.output, "{:?}", memo)?;
+        }
+        Ok(())
+    }
+
+    pub fn show_memo(&mut self, memos: &Memos, config: &Config) -> Result<()> {
+        let mut list = memos.get_all();
+        config.sort.sort(&mut list);
+        for memo in list {
+            writeln!(self.output, "{}", memo.title)?;
+        }
+
+        writeln!(self.output, "Enter the memo title to show:")?;
+        let title = match self.get_input()? {
+            Some(input) => input,
+            None => return Ok(()),
+        };
+
+        match memos.get_one(&title) {
+            Some(memo) => {
+                let rendered =
+                    render::to_terminal(memo, self.width, self.color, &config.date_format);
+                write!(self.output, "{}", rendered)?;
+            }
+            None => writeln!(self.output, "Not found memo")?,
+        }
+        Ok(())
+    }
+
+    pub fn show_stats(&mut self, memos: &Memos, config: &Config) -> Result<()> {
+        writeln!(self.output, "Number of weeks to cover (default 4):")?;
+        let weeks = match self.get_input()? {
+            Some(input) => match input.parse() {
+                Ok(weeks) if (1..=MAX_WEEKS).contains(&weeks) => weeks,
+                _ => {
+                    writeln!(self.output, "Invalid number")?;
+                    return Ok(());
+                }
+            },
+            None => 4,
+        };
+
+        let stats = Stats::collect(memos, weeks, Local::now().date_naive());
+        write!(self.output, "{}", stats.to_text(&config.date_format))?;
+        Ok(())
+    }
+}
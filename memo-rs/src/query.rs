@@ -0,0 +1,119 @@
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone};
+use regex::Regex;
+use thiserror::Error;
+
+use crate::memo::{Memo, Memos};
+
+#[derive(Debug, Error)]
+pub enum QueryError {
+    #[error("invalid title pattern: {0}")]
+    Glob(#[from] glob::PatternError),
+    #[error("invalid text regex: {0}")]
+    Regex(#[from] regex::Error),
+    #[error("invalid date {0:?}, expected YYYY-MM-DD or a duration like 30d or 4w")]
+    Date(String),
+    #[error("the query is empty, give at least one of --title, --tag, --older-than or --text")]
+    Empty,
+}
+
+/// Selects memos. Every given condition has to match.
+#[derive(Debug, Default)]
+pub struct Query {
+    /// Glob on the title, e.g. `meeting-*`
+    pub title: Option<glob::Pattern>,
+    /// Exact tag
+    pub tag: Option<String>,
+    /// Last edited before this time
+    pub older_than: Option<DateTime<Local>>,
+    /// Regex searched in the text
+    pub text: Option<Regex>,
+}
+
+impl Query {
+    /// Builds a query from its command line form. `older_than` is either a
+    /// date or a number of days or weeks before `now`.
+    pub fn parse(
+        title: Option<&str>,
+        tag: Option<&str>,
+        older_than: Option<&str>,
+        text: Option<&str>,
+        now: DateTime<Local>,
+    ) -> Result<Self, QueryError> {
+        let query = Self {
+            title: title.map(glob::Pattern::new).transpose()?,
+            tag: tag.map(str::to_owned),
+            older_than: older_than
+                .map(|input| parse_older_than(input, now))
+                .transpose()?,
+            text: text.map(Regex::new).transpose()?,
+        };
+        if query.is_empty() {
+            return Err(QueryError::Empty);
+        }
+        Ok(query)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.tag.is_none()
+            && self.older_than.is_none()
+            && self.text.is_none()
+    }
+
+    pub fn matches(&self, memo: &Memo) -> bool {
+        if let Some(title) = &self.title {
+            if !title.matches(&memo.title) {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !memo.tags.contains(tag) {
+                return false;
+            }
+        }
+        if let Some(older_than) = &self.older_than {
+            if memo.updated_at >= *older_than {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            if !text.is_match(&memo.text) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the matching memos sorted by title.
+    pub fn select<'a>(&self, memos: &'a Memos) -> Vec<&'a Memo> {
+        let mut selected: Vec<_> = memos
+            .get_all()
+            .into_iter()
+            .filter(|memo| self.matches(memo))
+            .collect();
+        selected.sort_by(|a, b| a.title.cmp(&b.title));
+        selected
+    }
+}
+
+fn parse_older_than(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>, QueryError> {
+    let error = || QueryError::Date(input.to_owned());
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).ok_or_else(error)?;
+        return Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .ok_or_else(error);
+    }
+
+    let unit_at = input.char_indices().last().map_or(0, |(index, _)| index);
+    let (number, unit) = input.split_at(unit_at);
+    let number: u64 = number.parse().map_err(|_| error())?;
+    let days = match unit {
+        "d" => number,
+        "w" => number.checked_mul(7).ok_or_else(error)?,
+        _ => return Err(error()),
+    };
+    now.checked_sub_days(Days::new(days)).ok_or_else(error)
+}
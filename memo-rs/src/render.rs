@@ -0,0 +1,391 @@
+use pulldown_cmark::{html, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::memo::Memo;
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const ITALIC: &str = "\x1b[3m";
+const UNDERLINE: &str = "\x1b[4m";
+const STRIKE: &str = "\x1b[9m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+const MIN_WIDTH: usize = 20;
+const CODE_INDENT: &str = "    ";
+const LIST_INDENT: &str = "   ";
+
+fn parser(text: &str) -> Parser<'_> {
+    Parser::new_ext(
+        text,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS,
+    )
+}
+
+/// Renders `memo` as styled text for a terminal `width` columns wide.
+/// ANSI styles are left out when `color` is false.
+pub fn to_terminal(memo: &Memo, width: usize, color: bool, date_format: &str) -> String {
+    let mut renderer = TerminalRenderer::new(width.max(MIN_WIDTH), color);
+
+    renderer.push_style(BOLD);
+    renderer.push_style(UNDERLINE);
+    renderer.text.push_str(&memo.title);
+    renderer.pop_style();
+    renderer.pop_style();
+    renderer.flush();
+
+    let mut meta = format!("updated {}", memo.updated_at.format(date_format));
+    if let Some(notebook) = &memo.notebook {
+        meta.push_str(&format!(" · notebook: {}", notebook));
+    }
+    if !memo.tags.is_empty() {
+        meta.push_str(&format!(" · tags: {}", memo.tags.join(", ")));
+    }
+    if memo.archived {
+        meta.push_str(" · archived");
+    }
+    renderer.push_style(DIM);
+    renderer.text.push_str(&meta);
+    renderer.pop_style();
+    renderer.flush();
+    renderer.out.push('\n');
+
+    for event in parser(&memo.text) {
+        renderer.event(event);
+    }
+    renderer.flush();
+
+    let trimmed = renderer.out.trim_end().len();
+    renderer.out.truncate(trimmed);
+    renderer.out.push('\n');
+    renderer.out
+}
+
+struct TerminalRenderer {
+    width: usize,
+    color: bool,
+    out: String,
+    // 현재 블록의 인라인 텍스트. 블록이 끝날 때 줄바꿈해서 out에 옮긴다.
+    text: String,
+    styles: Vec<&'static str>,
+    lists: Vec<Option<u64>>,
+    marker: Option<String>,
+    quotes: usize,
+    links: Vec<String>,
+    code: Option<String>,
+}
+
+impl TerminalRenderer {
+    fn new(width: usize, color: bool) -> Self {
+        Self {
+            width,
+            color,
+            out: String::new(),
+            text: String::new(),
+            styles: vec![],
+            lists: vec![],
+            marker: None,
+            quotes: 0,
+            links: vec![],
+            code: None,
+        }
+    }
+
+    fn push_style(&mut self, style: &'static str) {
+        self.styles.push(style);
+        if self.color {
+            self.text.push_str(style);
+        }
+    }
+
+    fn pop_style(&mut self) {
+        self.styles.pop();
+        if self.color {
+            self.text.push_str(RESET);
+            for style in &self.styles {
+                self.text.push_str(style);
+            }
+        }
+    }
+
+    fn indent(&self) -> String {
+        "│ ".repeat(self.quotes) + &LIST_INDENT.repeat(self.lists.len())
+    }
+
+    fn blank_line(&mut self) {
+        if self.lists.is_empty() && !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn flush(&mut self) {
+        let marker = self.marker.take();
+        if self.text.trim().is_empty() && marker.is_none() {
+            self.text.clear();
+            return;
+        }
+
+        let subsequent = self.indent();
+        let initial = match &marker {
+            Some(marker) => {
+                let outer = "│ ".repeat(self.quotes)
+                    + &LIST_INDENT.repeat(self.lists.len().saturating_sub(1));
+                format!("{}{:<width$}", outer, marker, width = LIST_INDENT.len())
+            }
+            None => subsequent.clone(),
+        };
+
+        let options = textwrap::Options::new(self.width)
+            .initial_indent(&initial)
+            .subsequent_indent(&subsequent);
+        for line in textwrap::wrap(self.text.trim(), options) {
+            self.out.push_str(line.trim_end());
+            self.out.push('\n');
+        }
+        self.text.clear();
+    }
+
+    fn event(&mut self, event: Event) {
+        if let Some(code) = &mut self.code {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => self.end_code_block(),
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text.push_str(&text),
+            Event::Code(code) => {
+                self.push_style(CYAN);
+                self.text.push_str(&code);
+                self.pop_style();
+            }
+            Event::SoftBreak => self.text.push(' '),
+            Event::HardBreak => self.text.push('\n'),
+            Event::Rule => {
+                self.flush();
+                let indent = self.indent();
+                let rule = "─".repeat(self.width.saturating_sub(indent.chars().count()));
+                self.out.push_str(&format!("{}{}\n", indent, rule));
+                self.blank_line();
+            }
+            Event::TaskListMarker(checked) => {
+                self.text.push_str(if checked { "[x] " } else { "[ ] " });
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.text.push_str(&html),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush();
+                self.push_style(BOLD);
+                if level == HeadingLevel::H1 {
+                    self.push_style(UNDERLINE);
+                }
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quotes += 1;
+            }
+            Tag::CodeBlock(_) => {
+                self.flush();
+                self.code = Some(String::new());
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_owned(),
+                };
+                self.marker = Some(marker);
+            }
+            Tag::Emphasis => self.push_style(ITALIC),
+            Tag::Strong => self.push_style(BOLD),
+            Tag::Strikethrough => self.push_style(STRIKE),
+            Tag::Link { dest_url, .. } => {
+                self.push_style(UNDERLINE);
+                self.links.push(dest_url.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(level) => {
+                if level == HeadingLevel::H1 {
+                    self.pop_style();
+                }
+                self.pop_style();
+                self.flush();
+                self.blank_line();
+            }
+            TagEnd::Paragraph => {
+                self.flush();
+                self.blank_line();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.quotes -= 1;
+                self.blank_line();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                self.blank_line();
+            }
+            TagEnd::Item => self.flush(),
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => self.pop_style(),
+            TagEnd::Link => {
+                self.pop_style();
+                if let Some(url) = self.links.pop() {
+                    self.text.push_str(&format!(" ({})", url));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end_code_block(&mut self) {
+        let code = self.code.take().unwrap_or_default();
+        let indent = self.indent() + CODE_INDENT;
+        for line in code.trim_end_matches('\n').lines() {
+            if self.color {
+                self.out
+                    .push_str(&format!("{}{}{}{}\n", indent, DIM, line, RESET));
+            } else {
+                self.out.push_str(&format!("{}{}\n", indent, line));
+            }
+        }
+        self.blank_line();
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ max-width: 48em; margin: 2em auto; padding: 0 1em; font-family: sans-serif; line-height: 1.5; }}
+pre {{ background: #f4f4f4; padding: 1em; overflow-x: auto; }}
+.meta {{ color: #777; }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+        escape(title),
+        body
+    )
+}
+
+/// Turns a memo title into a file name that is safe on every platform.
+pub fn file_name(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "memo".to_owned()
+    } else {
+        slug.to_owned()
+    }
+}
+
+/// Renders `memo` as a standalone HTML page. `index` is the link back to the
+/// collection index, if there is one.
+pub fn to_html(memo: &Memo, index: Option<&str>, date_format: &str) -> String {
+    let mut body = String::new();
+    if let Some(index) = index {
+        body.push_str(&format!(
+            "<p><a href=\"{}\">&larr; All memos</a></p>\n",
+            escape(index)
+        ));
+    }
+    body.push_str(&format!("<h1>{}</h1>\n", escape(&memo.title)));
+
+    let mut meta = format!("Updated {}", memo.updated_at.format(date_format));
+    if let Some(notebook) = &memo.notebook {
+        meta.push_str(&format!(" · notebook: {}", escape(notebook)));
+    }
+    if !memo.tags.is_empty() {
+        meta.push_str(&format!(" · tags: {}", escape(&memo.tags.join(", "))));
+    }
+    if memo.archived {
+        meta.push_str(" · archived");
+    }
+    body.push_str(&format!("<p class=\"meta\">{}</p>\n", meta));
+
+    // 가져온 메모에 스크립트가 들어 있을 수 있으니 HTML은 글자로 내보낸다.
+    let events = parser(&memo.text).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        event => event,
+    });
+    html::push_html(&mut body, events);
+    page(&memo.title, &body)
+}
+
+/// Writes one page per memo and an `index.html` linking to them into `dir`.
+pub fn export_collection(memos: &[&Memo], dir: &Path, date_format: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    // 목록 페이지 이름은 메모가 쓰지 못하게 미리 잡아 둔다.
+    let mut used = HashSet::from(["index.html".to_owned()]);
+    let mut items = String::new();
+    for memo in memos {
+        let slug = file_name(&memo.title);
+        let mut name = format!("{}.html", slug);
+        let mut n = 2;
+        while !used.insert(name.clone()) {
+            name = format!("{}-{}.html", slug, n);
+            n += 1;
+        }
+
+        fs::write(
+            dir.join(&name),
+            to_html(memo, Some("index.html"), date_format),
+        )?;
+        items.push_str(&format!(
+            "<li><a href=\"{}\">{}</a> <span class=\"meta\">{}</span></li>\n",
+            escape(&name),
+            escape(&memo.title),
+            memo.updated_at.format(date_format)
+        ));
+    }
+
+    let body = format!("<h1>Memos</h1>\n<ul>\n{}</ul>\n", items);
+    fs::write(dir.join("index.html"), page("Memos", &body))
+}
@@ -0,0 +1,332 @@
+//! A command line shell over the memo store, with persistent history, line
+//! editing and tab completion of command names and memo titles.
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::{CompletionType, Context, Editor, Helper, Highlighter, Hinter, Validator};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::commands;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::memo::{Memo, Memos};
+
+/// Command names with their usage, in the order `help` shows them.
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("add", "add <title> <text>   Add a memo"),
+    ("ls", "ls [--archived]      List memos"),
+    ("show", "show <title>         Render a memo"),
+    ("edit", "edit <title>         Edit a memo in the editor"),
+    ("rm", "rm <title>           Remove a memo"),
+    ("stats", "stats [weeks]        Print statistics"),
+    ("help", "help                 Show this help"),
+    ("quit", "quit                 Leave (also exit or Ctrl+D)"),
+];
+
+// 제목을 인자로 받는 명령. 두 번째 단어에서 제목을 완성한다.
+const TITLE_COMMANDS: &[&str] = &["show", "edit", "rm"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Splits a line into words. Single or double quotes keep spaces in a word.
+pub fn split_args(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut args = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => {
+                if let Some(word) = word.take() {
+                    args.push(word);
+                }
+            }
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return Err("unterminated quote".to_owned());
+    }
+    if let Some(word) = word {
+        args.push(word);
+    }
+    Ok(args)
+}
+
+/// Quotes `title` so that [`split_args`] reads it back as one word.
+fn quote_title(title: &str) -> String {
+    if title.contains('"') && title.contains('\'') {
+        // 따옴표로 감싼 조각은 한 단어로 이어지므로, 큰따옴표만 작은따옴표로 감싼다.
+        title
+            .split_inclusive('"')
+            .map(|part| match part.strip_suffix('"') {
+                Some(part) => format!("\"{}\"'\"'", part),
+                None => format!("\"{}\"", part),
+            })
+            .collect()
+    } else if title.contains(char::is_whitespace) || title.contains(['"', '\'']) {
+        let q = if title.contains('"') { '\'' } else { '"' };
+        format!("{}{}{}", q, title, q)
+    } else {
+        title.to_owned()
+    }
+}
+
+/// Runs shell commands against one memo store, saving after every change.
+pub struct Repl<'a> {
+    memos: Memos,
+    config: &'a Config,
+    /// Width used when rendering a memo
+    pub width: usize,
+    /// Whether rendered memos use ANSI styles
+    pub color: bool,
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(config: &'a Config) -> Result<Self> {
+        Ok(Self {
+            memos: Memos::load(&config.store)?,
+            config,
+            width: 80,
+            color: false,
+        })
+    }
+
+    pub fn memos(&self) -> &Memos {
+        &self.memos
+    }
+
+    fn titles(&self) -> Vec<String> {
+        let mut titles: Vec<String> = self
+            .memos
+            .get_all()
+            .into_iter()
+            .map(|memo| memo.title.to_owned())
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    /// Runs one command line. Errors about the command itself, like an
+    /// unknown title, are returned so the caller can print them and go on.
+    pub fn execute(&mut self, line: &str, out: &mut impl Write) -> Result<Flow> {
+        let args = split_args(line).map_err(Error::Usage)?;
+        let (command, args) = match args.split_first() {
+            Some((command, args)) => (command.as_str(), args),
+            None => return Ok(Flow::Continue),
+        };
+        let usage = || {
+            let usage = COMMANDS
+                .iter()
+                .find(|(name, _)| *name == command)
+                .map_or("", |(_, usage)| usage);
+            Error::Usage(format!("usage: {}", usage))
+        };
+
+        let changed = match (command, args) {
+            ("add", [title, text @ ..]) if !text.is_empty() => {
+                let memo = Memo::new(title.to_owned(), text.join(" "));
+                self.memos.add(memo)?;
+                writeln!(out, "Memo Added")?;
+                true
+            }
+            ("ls", []) => {
+                commands::list(&self.memos, false, self.config, out)?;
+                false
+            }
+            ("ls", [flag]) if flag == "--archived" => {
+                commands::list(&self.memos, true, self.config, out)?;
+                false
+            }
+            ("show", [title]) => {
+                commands::show(&self.memos, title, self.config, self.width, self.color, out)?;
+                false
+            }
+            ("edit", [title]) => commands::edit(&mut self.memos, title, self.config, out)?,
+            ("rm", [title]) => {
+                self.memos.remove(title)?;
+                writeln!(out, "Removed!")?;
+                true
+            }
+            ("stats", []) => {
+                commands::stats(&self.memos, 4, self.config, out)?;
+                false
+            }
+            ("stats", [weeks]) => {
+                let weeks = weeks.parse().map_err(|_| usage())?;
+                commands::stats(&self.memos, weeks, self.config, out)?;
+                false
+            }
+            ("help", _) => {
+                for (_, usage) in COMMANDS {
+                    writeln!(out, "{}", usage)?;
+                }
+                false
+            }
+            ("quit" | "exit", _) => return Ok(Flow::Quit),
+            ("add" | "ls" | "show" | "edit" | "rm" | "stats", _) => return Err(usage()),
+            _ => {
+                return Err(Error::Usage(format!(
+                    "unknown command: {}, try help",
+                    command
+                )))
+            }
+        };
+
+        if changed {
+            self.memos.save(&self.config.store)?;
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Reads commands from the terminal until `quit` or Ctrl+D. History is
+    /// kept next to the memo store.
+    pub fn run(&mut self) -> Result<()> {
+        let history = history_path(&self.config.store);
+        let editor_config = rustyline::Config::builder()
+            .auto_add_history(true)
+            .history_ignore_dups(true)?
+            .history_ignore_space(true)
+            .completion_type(CompletionType::List)
+            .build();
+        let mut editor = Editor::with_config(editor_config)?;
+        editor.set_helper(Some(ReplHelper {
+            titles: self.titles(),
+        }));
+        // 처음 실행할 때는 기록 파일이 없다.
+        let _ = editor.load_history(&history);
+
+        let mut out = io::stdout();
+        loop {
+            let line = match editor.readline("memo> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            match self.execute(&line, &mut out) {
+                Ok(Flow::Continue) => {}
+                Ok(Flow::Quit) => break,
+                Err(Error::Io(e)) => return Err(e.into()),
+                Err(e) => writeln!(out, "{}", e)?,
+            }
+            if let Some(helper) = editor.helper_mut() {
+                helper.titles = self.titles();
+            }
+        }
+
+        if let Some(parent) = history.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        editor.save_history(&history)?;
+        Ok(())
+    }
+}
+
+fn history_path(store: &std::path::Path) -> PathBuf {
+    let mut name = store.file_stem().unwrap_or_default().to_owned();
+    name.push(".history");
+    store.with_file_name(name)
+}
+
+/// Completes command names in the first word and memo titles after a
+/// command that takes one.
+#[derive(Helper, Hinter, Highlighter, Validator)]
+pub struct ReplHelper {
+    pub titles: Vec<String>,
+}
+
+impl ReplHelper {
+    /// Returns where the word under the cursor starts and its completions.
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+
+        // 따옴표 안의 공백은 단어를 나누지 않는다.
+        let mut words = 0;
+        let mut start = 0;
+        let mut quote = None;
+        let mut in_word = false;
+        for (index, c) in before.char_indices() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c.is_whitespace() => {
+                    if in_word {
+                        words += 1;
+                        in_word = false;
+                    }
+                }
+                None => {
+                    if !in_word {
+                        start = index;
+                        in_word = true;
+                    }
+                    if c == '"' || c == '\'' {
+                        quote = Some(c);
+                    }
+                }
+            }
+        }
+        if !in_word {
+            start = pos;
+        }
+
+        let prefix = before[start..].trim_start_matches(['"', '\'']);
+        let completions = match words {
+            0 => COMMANDS
+                .iter()
+                .map(|(name, _)| *name)
+                .filter(|name| name.starts_with(prefix))
+                .map(str::to_owned)
+                .collect(),
+            1 => {
+                let command = before.split_whitespace().next().unwrap_or_default();
+                if TITLE_COMMANDS.contains(&command) {
+                    self.titles
+                        .iter()
+                        .filter(|title| title.starts_with(prefix))
+                        .map(|title| quote_title(title))
+                        .collect()
+                } else {
+                    vec![]
+                }
+            }
+            _ => vec![],
+        };
+        (start, completions)
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, completions) = self.candidates(line, pos);
+        let pairs = completions
+            .into_iter()
+            .map(|completion| Pair {
+                display: completion.trim_matches(['"', '\'']).to_owned(),
+                replacement: completion,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::{DateTime, Days, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::memo::Memos;
+
+const RECENT_LIMIT: usize = 5;
+const BAR_WIDTH: usize = 40;
+/// The most weeks of activity a report covers, ten years.
+pub const MAX_WEEKS: u32 = 520;
+
+#[derive(Debug, Serialize)]
+pub struct RecentMemo {
+    pub title: String,
+    pub updated_at: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayActivity {
+    pub date: NaiveDate,
+    pub created: usize,
+    pub edited: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub total_memos: usize,
+    pub total_words: usize,
+    pub total_chars: usize,
+    pub per_tag: BTreeMap<String, usize>,
+    pub per_notebook: BTreeMap<String, usize>,
+    pub recently_edited: Vec<RecentMemo>,
+    pub activity: Vec<DayActivity>,
+}
+
+impl Stats {
+    /// Collects the report for `memos`, with an activity row for each of the
+    /// last `weeks` weeks of days ending at `today`.
+    ///
+    /// A memo only keeps its latest edit time, so an edit is counted on the
+    /// day the memo was last updated.
+    pub fn collect(memos: &Memos, weeks: u32, today: NaiveDate) -> Self {
+        let all = memos.get_all();
+
+        let mut per_tag = BTreeMap::new();
+        let mut per_notebook = BTreeMap::new();
+        for memo in &all {
+            for tag in &memo.tags {
+                *per_tag.entry(tag.to_owned()).or_insert(0) += 1;
+            }
+            if let Some(notebook) = &memo.notebook {
+                *per_notebook.entry(notebook.to_owned()).or_insert(0) += 1;
+            }
+        }
+
+        let mut recent: Vec<_> = all.iter().collect();
+        recent.sort_by_key(|memo| std::cmp::Reverse(memo.updated_at));
+        let recently_edited = recent
+            .into_iter()
+            .take(RECENT_LIMIT)
+            .map(|memo| RecentMemo {
+                title: memo.title.to_owned(),
+                updated_at: memo.updated_at,
+            })
+            .collect();
+
+        let days = u64::from(weeks) * 7;
+        let start = today
+            .checked_sub_days(Days::new(days.saturating_sub(1)))
+            .unwrap_or(today);
+        let mut activity: Vec<DayActivity> = start
+            .iter_days()
+            .take(days as usize)
+            .map(|date| DayActivity {
+                date,
+                created: 0,
+                edited: 0,
+            })
+            .collect();
+
+        for memo in &all {
+            let created = memo.created_at.date_naive();
+            if let Some(day) = day_index(start, created, activity.len()) {
+                activity[day].created += 1;
+            }
+            if memo.updated_at != memo.created_at {
+                let edited = memo.updated_at.date_naive();
+                if let Some(day) = day_index(start, edited, activity.len()) {
+                    activity[day].edited += 1;
+                }
+            }
+        }
+
+        Self {
+            total_memos: all.len(),
+            total_words: all
+                .iter()
+                .map(|memo| memo.text.split_whitespace().count())
+                .sum(),
+            total_chars: all.iter().map(|memo| memo.text.chars().count()).sum(),
+            per_tag,
+            per_notebook,
+            recently_edited,
+            activity,
+        }
+    }
+
+    pub fn to_text(&self, date_format: &str) -> String {
+        let mut out = String::new();
+
+        // String에 대한 write!는 실패하지 않는다.
+        let _ = writeln!(out, "== Memo Statistics ==");
+        let _ = writeln!(out, "Memos:      {}", self.total_memos);
+        let _ = writeln!(out, "Words:      {}", self.total_words);
+        let _ = writeln!(out, "Characters: {}", self.total_chars);
+
+        let _ = writeln!(out, "\n-- Per tag --");
+        write_counts(&mut out, &self.per_tag);
+        let _ = writeln!(out, "\n-- Per notebook --");
+        write_counts(&mut out, &self.per_notebook);
+
+        let _ = writeln!(out, "\n-- Recently edited --");
+        if self.recently_edited.is_empty() {
+            let _ = writeln!(out, "(none)");
+        }
+        for memo in &self.recently_edited {
+            let _ = writeln!(
+                out,
+                "{}  {}",
+                memo.updated_at.format(date_format),
+                memo.title
+            );
+        }
+
+        let _ = writeln!(out, "\n-- Activity (+ created, ~ edited) --");
+        let max = self
+            .activity
+            .iter()
+            .map(|day| day.created + day.edited)
+            .max()
+            .unwrap_or(0);
+        for day in &self.activity {
+            let (created, edited) = scale_bar(day.created, day.edited, max);
+            let _ = writeln!(
+                out,
+                "{} {:>3} {}{}",
+                day.date.format("%Y-%m-%d %a"),
+                day.created + day.edited,
+                "+".repeat(created),
+                "~".repeat(edited)
+            );
+        }
+
+        out
+    }
+}
+
+fn day_index(start: NaiveDate, date: NaiveDate, len: usize) -> Option<usize> {
+    let offset = (date - start).num_days();
+    if offset < 0 || offset as usize >= len {
+        None
+    } else {
+        Some(offset as usize)
+    }
+}
+
+fn scale_bar(created: usize, edited: usize, max: usize) -> (usize, usize) {
+    if max <= BAR_WIDTH {
+        return (created, edited);
+    }
+    let scale = |count: usize| (count * BAR_WIDTH).div_ceil(max);
+    (scale(created), scale(edited))
+}
+
+fn write_counts(out: &mut String, counts: &BTreeMap<String, usize>) {
+    if counts.is_empty() {
+        let _ = writeln!(out, "(none)");
+    }
+    for (name, count) in counts {
+        let _ = writeln!(out, "{:<20} {}", name, count);
+    }
+}
@@ -0,0 +1,163 @@
+//! Change events for memos, both from a [`Memos`] collection in this process
+//! (see [`Memos::subscribe`]) and from a store file edited by any process
+//! (see [`StoreWatcher`]).
+
+use chrono::{DateTime, Local};
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::memo::Memos;
+
+// 저장 한 번에도 이벤트가 여러 개 오므로 잠시 모아서 처리한다.
+const SETTLE: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Updated,
+    Removed,
+}
+
+/// One change to one memo, serialized as
+/// `{"event": "added", "title": "...", "timestamp": "..."}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangeEvent {
+    #[serde(rename = "event")]
+    pub kind: ChangeKind,
+    pub title: String,
+    pub timestamp: DateTime<Local>,
+}
+
+impl ChangeEvent {
+    pub fn new(kind: ChangeKind, title: &str) -> Self {
+        Self {
+            kind,
+            title: title.to_owned(),
+            timestamp: Local::now(),
+        }
+    }
+}
+
+/// Lists the changes that turn `old` into `new`, ordered by title.
+pub fn diff(old: &Memos, new: &Memos) -> Vec<ChangeEvent> {
+    let mut events = vec![];
+
+    for memo in new.get_all() {
+        match old.get_one(&memo.title) {
+            None => events.push(ChangeEvent {
+                kind: ChangeKind::Added,
+                title: memo.title.to_owned(),
+                timestamp: memo.created_at,
+            }),
+            Some(previous) if previous != memo => events.push(ChangeEvent {
+                kind: ChangeKind::Updated,
+                title: memo.title.to_owned(),
+                timestamp: memo.updated_at,
+            }),
+            Some(_) => {}
+        }
+    }
+    for memo in old.get_all() {
+        if new.get_one(&memo.title).is_none() {
+            events.push(ChangeEvent::new(ChangeKind::Removed, &memo.title));
+        }
+    }
+
+    events.sort_by(|a, b| a.title.cmp(&b.title));
+    events
+}
+
+fn stopped() -> Error {
+    notify::Error::generic("the file watcher stopped").into()
+}
+
+/// Watches a memo store file and reports the memos changed in it by any
+/// process.
+pub struct StoreWatcher {
+    path: PathBuf,
+    current: Memos,
+    events: Receiver<notify::Result<notify::Event>>,
+    // 드롭되면 감시가 멈추므로 들고 있어야 한다.
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl StoreWatcher {
+    /// Starts watching the store at `path`. The store doesn't need to exist yet.
+    pub fn new(path: &Path) -> Result<Self> {
+        // 저장은 임시 파일을 rename하는 방식이라 파일이 아닌 디렉터리를 감시한다.
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        fs::create_dir_all(&dir)?;
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            current: Memos::load(path)?,
+            events: rx,
+            _watcher: watcher,
+        })
+    }
+
+    /// The memos as of the last reported change.
+    pub fn memos(&self) -> &Memos {
+        &self.current
+    }
+
+    fn touches_store(&self, event: &notify::Event) -> bool {
+        let name = self.path.file_name();
+        event
+            .paths
+            .iter()
+            .any(|path| path.file_name().is_some() && path.file_name() == name)
+    }
+
+    /// Waits until the store changes and returns the resulting events.
+    /// Returns an empty list if nothing changed within `timeout`.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<Vec<ChangeEvent>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let event = match deadline {
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    match self.events.recv_timeout(left) {
+                        Ok(event) => event,
+                        Err(RecvTimeoutError::Timeout) => return Ok(vec![]),
+                        Err(RecvTimeoutError::Disconnected) => return Err(stopped()),
+                    }
+                }
+                None => match self.events.recv() {
+                    Ok(event) => event,
+                    Err(_) => return Err(stopped()),
+                },
+            };
+            if !self.touches_store(&event?) {
+                continue;
+            }
+
+            while self.events.recv_timeout(SETTLE).is_ok() {}
+
+            // 다른 프로세스가 쓰는 중이라 읽을 수 없으면 다음 이벤트를 기다린다.
+            let memos = match Memos::load(&self.path) {
+                Ok(memos) => memos,
+                Err(_) => continue,
+            };
+            let changes = diff(&self.current, &memos);
+            self.current = memos;
+            if !changes.is_empty() {
+                return Ok(changes);
+            }
+        }
+    }
+}
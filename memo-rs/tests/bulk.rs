@@ -0,0 +1,173 @@
+use chrono::{Local, TimeZone};
+use memo_rs::commands::{self, BulkAction};
+use memo_rs::query::{Query, QueryError};
+use memo_rs::{Memo, Memos};
+
+fn memos() -> Memos {
+    let mut memos = Memos::new();
+    for (title, text, tag, day) in [
+        ("meeting-01", "budget review", "work", 1),
+        ("meeting-02", "hiring plan", "work", 20),
+        ("recipe", "flour and sugar", "home", 2),
+    ] {
+        let mut memo = Memo::new(title.to_owned(), text.to_owned());
+        memo.tags = vec![tag.to_owned()];
+        memo.updated_at = Local.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap();
+        memos.add(memo).unwrap();
+    }
+    memos
+}
+
+fn query(title: Option<&str>, tag: Option<&str>, older: Option<&str>, text: Option<&str>) -> Query {
+    let now = Local.with_ymd_and_hms(2024, 3, 22, 12, 0, 0).unwrap();
+    Query::parse(title, tag, older, text, now).unwrap()
+}
+
+fn titles(query: &Query, memos: &Memos) -> Vec<String> {
+    query
+        .select(memos)
+        .into_iter()
+        .map(|memo| memo.title.to_owned())
+        .collect()
+}
+
+#[test]
+fn selectors_combine() {
+    let memos = memos();
+
+    assert_eq!(
+        titles(&query(Some("meeting-*"), None, None, None), &memos),
+        ["meeting-01", "meeting-02"]
+    );
+    assert_eq!(
+        titles(&query(None, Some("home"), None, None), &memos),
+        ["recipe"]
+    );
+    assert_eq!(
+        titles(&query(None, None, Some("2w"), None), &memos),
+        ["meeting-01", "recipe"]
+    );
+    assert_eq!(
+        titles(&query(None, None, Some("2024-03-02"), None), &memos),
+        ["meeting-01"]
+    );
+    assert_eq!(
+        titles(&query(None, None, None, Some("^(budget|hiring)")), &memos),
+        ["meeting-01", "meeting-02"]
+    );
+    assert_eq!(
+        titles(&query(Some("meeting-*"), None, Some("10d"), None), &memos),
+        ["meeting-01"]
+    );
+}
+
+#[test]
+fn invalid_queries_are_rejected() {
+    let now = Local::now();
+    assert!(matches!(
+        Query::parse(None, None, None, None, now),
+        Err(QueryError::Empty)
+    ));
+    assert!(matches!(
+        Query::parse(Some("[a"), None, None, None, now),
+        Err(QueryError::Glob(_))
+    ));
+    assert!(matches!(
+        Query::parse(None, None, None, Some("("), now),
+        Err(QueryError::Regex(_))
+    ));
+    for date in ["yesterday", "3x", "d", "2024-13-01", "3일"] {
+        assert!(matches!(
+            Query::parse(None, None, Some(date), None, now),
+            Err(QueryError::Date(_))
+        ));
+    }
+}
+
+#[test]
+fn bulk_asks_for_confirmation() {
+    let mut memos = memos();
+    let query = query(Some("meeting-*"), None, None, None);
+
+    let mut out = Vec::new();
+    let changed = commands::bulk(
+        &mut memos,
+        &query,
+        &BulkAction::Delete,
+        false,
+        &mut "n\n".as_bytes(),
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(changed, 0);
+    assert_eq!(memos.len(), 3);
+    let preview = String::from_utf8(out).unwrap();
+    assert!(preview.contains("  meeting-01\n  meeting-02\n2 memos will be affected: delete"));
+    assert!(preview.contains("Cancelled"));
+
+    let changed = commands::bulk(
+        &mut memos,
+        &query,
+        &BulkAction::Delete,
+        false,
+        &mut "y\n".as_bytes(),
+        &mut Vec::new(),
+    )
+    .unwrap();
+    assert_eq!(changed, 2);
+    assert_eq!(memos.len(), 1);
+}
+
+#[test]
+fn bulk_tag_archive_and_move() {
+    let mut memos = memos();
+    let work = query(None, Some("work"), None, None);
+    let run = |memos: &mut Memos, action: BulkAction| {
+        commands::bulk(
+            memos,
+            &work,
+            &action,
+            true,
+            &mut "".as_bytes(),
+            &mut Vec::new(),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        run(
+            &mut memos,
+            BulkAction::Tag(vec!["q1".to_owned(), "work".to_owned()])
+        ),
+        2
+    );
+    assert_eq!(memos.get_one("meeting-01").unwrap().tags, ["work", "q1"]);
+
+    run(&mut memos, BulkAction::Archive);
+    run(&mut memos, BulkAction::Move(Some("office".to_owned())));
+    let memo = memos.get_one("meeting-02").unwrap();
+    assert!(memo.archived);
+    assert_eq!(memo.notebook.as_deref(), Some("office"));
+    assert!(!memos.get_one("recipe").unwrap().archived);
+
+    run(&mut memos, BulkAction::Untag(vec!["q1".to_owned()]));
+    assert_eq!(memos.get_one("meeting-01").unwrap().tags, ["work"]);
+}
+
+#[test]
+fn empty_selection_changes_nothing() {
+    let mut memos = memos();
+    let query = query(Some("nothing*"), None, None, None);
+    let mut out = Vec::new();
+    let changed = commands::bulk(
+        &mut memos,
+        &query,
+        &BulkAction::Delete,
+        false,
+        &mut "".as_bytes(),
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(changed, 0);
+    assert!(String::from_utf8(out).unwrap().contains("No memo matches"));
+}
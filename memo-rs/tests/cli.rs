@@ -0,0 +1,42 @@
+use std::process::Command;
+
+fn memo_rs(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_memo-rs"))
+        .arg("--config")
+        .arg(dir.join("config.toml"))
+        .arg("--store")
+        .arg(dir.join("memos.json"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn errors_go_to_stderr_with_failure_status() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = memo_rs(dir.path(), &["show", "missing"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("memo-rs: "), "{}", stderr);
+}
+
+#[test]
+fn stats_of_an_empty_store_succeeds() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = memo_rs(dir.path(), &["stats", "--format", "json"]);
+
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(stats.is_object());
+}
+
+#[test]
+fn stats_refuses_too_many_weeks() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = memo_rs(dir.path(), &["stats", "--weeks", "100000"]);
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
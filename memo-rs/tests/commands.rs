@@ -0,0 +1,266 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use memo_rs::config::{Config, OutputFormat, SortOrder};
+use memo_rs::import::{self, Outcome};
+use memo_rs::stats::Stats;
+use memo_rs::{commands, render, Error, Memo, Memos};
+use std::fs;
+
+fn memo(title: &str, text: &str, created: (u32, u32), updated: (u32, u32)) -> Memo {
+    let at = |(month, day)| Local.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+    Memo {
+        title: title.to_owned(),
+        text: text.to_owned(),
+        tags: vec![],
+        notebook: None,
+        archived: false,
+        created_at: at(created),
+        updated_at: at(updated),
+    }
+}
+
+fn output(f: impl FnOnce(&mut Vec<u8>)) -> String {
+    let mut out = Vec::new();
+    f(&mut out);
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn stats_counts_and_activity() {
+    let mut memos = Memos::new();
+    let mut a = memo("a", "one two", (3, 1), (3, 5));
+    a.tags = vec!["x".to_owned(), "y".to_owned()];
+    a.notebook = Some("work".to_owned());
+    let mut b = memo("b", "three", (3, 5), (3, 5));
+    b.tags = vec!["x".to_owned()];
+    memos.add(a).unwrap();
+    memos.add(b).unwrap();
+    memos.add(memo("old", "ancient", (1, 1), (1, 1))).unwrap();
+
+    let stats = Stats::collect(&memos, 1, NaiveDate::from_ymd_opt(2024, 3, 7).unwrap());
+
+    assert_eq!(stats.total_memos, 3);
+    assert_eq!(stats.total_words, 4);
+    assert_eq!(stats.total_chars, 19);
+    assert_eq!(stats.per_tag["x"], 2);
+    assert_eq!(stats.per_tag["y"], 1);
+    assert_eq!(stats.per_notebook["work"], 1);
+    assert_eq!(stats.recently_edited[2].title, "old");
+    assert_eq!(stats.activity.len(), 7);
+    assert_eq!(
+        stats.activity[0].date,
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    );
+    assert_eq!(
+        (stats.activity[0].created, stats.activity[0].edited),
+        (1, 0)
+    );
+    assert_eq!(
+        (stats.activity[4].created, stats.activity[4].edited),
+        (1, 1)
+    );
+}
+
+#[test]
+fn list_follows_sort_order_and_format() {
+    let mut memos = Memos::new();
+    memos.add(memo("b", "", (1, 1), (1, 9))).unwrap();
+    memos.add(memo("a", "", (1, 2), (1, 3))).unwrap();
+    let mut config = Config {
+        sort: SortOrder::Updated,
+        date_format: "%m-%d".to_owned(),
+        ..Config::default()
+    };
+
+    let text = output(|out| commands::list(&memos, false, &config, out).unwrap());
+    assert_eq!(text, "01-09  b\n01-03  a\n");
+
+    config.sort = SortOrder::Title;
+    config.format = OutputFormat::Json;
+    let json = output(|out| commands::list(&memos, false, &config, out).unwrap());
+    let list: Vec<Memo> = serde_json::from_str(&json).unwrap();
+    assert_eq!(list[0].title, "a");
+}
+
+#[test]
+fn show_missing_memo_fails() {
+    let memos = Memos::new();
+    let result = commands::show(&memos, "x", &Config::default(), 80, false, &mut Vec::new());
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+#[test]
+fn import_plan_reports_skips_and_conflicts() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("notes.csv");
+    fs::write(
+        &csv,
+        "title,text,tags\nnew,\"multi\nline\",\"a, b\"\nsame,kept,\nclash,other,\n,no title,\nnew,again,\n",
+    )
+    .unwrap();
+
+    let mut memos = Memos::new();
+    memos.add(memo("same", "kept", (1, 1), (1, 1))).unwrap();
+    memos.add(memo("clash", "mine", (1, 1), (1, 1))).unwrap();
+
+    let records = import::read(&csv, None).unwrap();
+    let plan = import::Plan::new(&memos, records);
+    let outcomes: Vec<_> = plan.entries.iter().map(|(_, outcome)| outcome).collect();
+    assert_eq!(outcomes[0], &Outcome::Created);
+    assert!(matches!(outcomes[1], Outcome::Skipped(_)));
+    assert_eq!(outcomes[2], &Outcome::Conflicted);
+    assert!(matches!(outcomes[3], Outcome::Skipped(_)));
+    assert_eq!(outcomes[4], &Outcome::Conflicted);
+
+    let text = output(|out| {
+        let added = commands::import(&mut memos, &csv, None, true, out).unwrap();
+        assert_eq!(added, 0);
+    });
+    assert!(text.contains("1 created, 2 skipped, 2 conflicted"));
+    assert!(memos.get_one("new").is_none());
+
+    output(|out| {
+        assert_eq!(
+            commands::import(&mut memos, &csv, None, false, out).unwrap(),
+            1
+        );
+    });
+    let new = memos.get_one("new").unwrap();
+    assert_eq!(new.text, "multi\nline");
+    assert_eq!(new.tags, vec!["a", "b"]);
+    assert_eq!(memos.get_one("clash").unwrap().text, "mine");
+}
+
+#[test]
+fn import_text_dir_and_json() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("first note.txt"), "hello\n").unwrap();
+    fs::write(dir.path().join("ignored.md"), "nope").unwrap();
+    let records = import::read(dir.path(), None).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].title, "first note");
+    assert_eq!(records[0].text, "hello");
+
+    let json = dir.path().join("notes.json");
+    fs::write(&json, r#"[{"title": "j", "text": "t", "notebook": "n"}]"#).unwrap();
+    let records = import::read(&json, None).unwrap();
+    assert_eq!(records[0].notebook.as_deref(), Some("n"));
+
+    // 다른 형식과 같이 공백을 정리한다.
+    fs::write(
+        &json,
+        r#"[{"title": " j ", "text": "t\n", "tags": [" a", ""], "notebook": " "}]"#,
+    )
+    .unwrap();
+    let records = import::read(&json, None).unwrap();
+    assert_eq!(records[0].title, "j");
+    assert_eq!(records[0].text, "t");
+    assert_eq!(records[0].tags, ["a"]);
+    assert_eq!(records[0].notebook, None);
+
+    let unknown = dir.path().join("notes.xml");
+    assert!(import::read(&unknown, None).is_err());
+}
+
+#[test]
+fn import_format_flag_and_csv_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.txt");
+    fs::write(
+        &export,
+        "text,notebook,title
+body,work,t
+",
+    )
+    .unwrap();
+    assert!(import::read(&export, None).is_err());
+
+    let records = import::read(&export, Some(import::Format::Csv)).unwrap();
+    assert_eq!(records[0].title, "t");
+    assert_eq!(records[0].notebook.as_deref(), Some("work"));
+
+    let no_text = dir.path().join("no_text.csv");
+    fs::write(
+        &no_text, "title
+t
+",
+    )
+    .unwrap();
+    assert!(matches!(
+        import::read(&no_text, None),
+        Err(import::ImportError::MissingColumn(column)) if column == "text"
+    ));
+}
+
+#[test]
+fn terminal_rendering_wraps_and_indents() {
+    let text = "# Title\n\nsome **bold** words that should wrap\n\n- one\n- two\n\n```\ncode\n```";
+    let rendered = render::to_terminal(&memo("m", text, (1, 1), (1, 1)), 20, false, "%Y");
+
+    assert!(rendered.contains("some bold words that\nshould wrap"));
+    assert!(rendered.contains("•  one\n•  two"));
+    assert!(rendered.contains("\n    code\n"));
+    assert!(!rendered.contains('\x1b'));
+}
+
+#[test]
+fn html_export_writes_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut memos = Memos::new();
+    memos
+        .add(memo("Hello World", "*hi* <b>", (1, 1), (1, 1)))
+        .unwrap();
+    memos
+        .add(memo("hello world!", "again", (1, 1), (1, 1)))
+        .unwrap();
+
+    output(|out| commands::export_html(&memos, dir.path(), None, &Config::default(), out).unwrap());
+
+    let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+    assert!(index.contains("hello-world.html"));
+    assert!(index.contains("hello-world-2.html"));
+    let page = fs::read_to_string(dir.path().join("hello-world.html")).unwrap();
+    assert!(page.contains("<em>hi</em>"));
+}
+
+#[test]
+fn html_escapes_raw_html() {
+    let text = "<script>alert(1)</script>\n\nsee <img src=x onerror=alert(1)>";
+    let page = render::to_html(&memo("m", text, (1, 1), (1, 1)), None, "%Y");
+
+    assert!(!page.contains("<script>"));
+    assert!(!page.contains("<img"));
+    assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(page.contains("&lt;img src=x onerror=alert(1)&gt;"));
+}
+
+#[test]
+fn html_export_keeps_index_for_memo_titled_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut memos = Memos::new();
+    memos
+        .add(memo("index", "not the list", (1, 1), (1, 1)))
+        .unwrap();
+
+    output(|out| commands::export_html(&memos, dir.path(), None, &Config::default(), out).unwrap());
+
+    let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+    assert!(index.contains("<h1>Memos</h1>"));
+    assert!(index.contains("index-2.html"));
+    let page = fs::read_to_string(dir.path().join("index-2.html")).unwrap();
+    assert!(page.contains("not the list"));
+}
+
+#[cfg(unix)]
+#[test]
+fn edit_runs_the_editor_on_a_temp_file() {
+    let mut memos = Memos::new();
+    memos.add(memo("a", "first", (3, 1), (3, 1))).unwrap();
+    let config = Config {
+        editor: "sed -i s/first/second/".to_owned(),
+        ..Config::default()
+    };
+
+    let text = output(|out| assert!(commands::edit(&mut memos, "a", &config, out).unwrap()));
+    assert_eq!(text, "Updated!\n");
+    assert_eq!(memos.get_one("a").unwrap().text, "second");
+}
@@ -0,0 +1,86 @@
+use memo_rs::config::{Config, ConfigError, OutputFormat, SortOrder};
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG: &str = r#"
+profile = "work"
+store = "top.json"
+format = "json"
+date_format = "%d/%m"
+
+[profiles.work]
+store = "work/memos.json"
+sort = "updated"
+
+[profiles.home]
+format = "text"
+date_format = "%Y"
+"#;
+
+fn load(profile: Option<&str>) -> (tempfile::TempDir, Result<Config, ConfigError>) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, CONFIG).unwrap();
+    let config = Config::load(Some(&path), profile);
+    (dir, config)
+}
+
+#[test]
+fn unknown_profile_is_an_error() {
+    let (_dir, config) = load(Some("missing"));
+    assert!(matches!(config, Err(ConfigError::UnknownProfile(name)) if name == "missing"));
+}
+
+#[test]
+fn profile_settings_win_over_top_level_and_defaults() {
+    // 파일의 profile 키가 고른 프로필
+    let (dir, config) = load(None);
+    let config = config.unwrap();
+    assert_eq!(config.store, dir.path().join("work/memos.json"));
+    assert_eq!(config.sort, SortOrder::Updated);
+    assert_eq!(config.format, OutputFormat::Json);
+    assert_eq!(config.date_format, "%d/%m");
+
+    // --profile이 파일의 profile 키보다 우선한다.
+    let (_dir, config) = load(Some("home"));
+    let config = config.unwrap();
+    assert_eq!(config.format, OutputFormat::Text);
+    assert_eq!(config.sort, SortOrder::Title);
+    assert_eq!(config.date_format, "%Y");
+}
+
+#[test]
+fn profile_without_store_uses_its_own_file() {
+    let (_dir, config) = load(Some("home"));
+    let expected = dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("memo-rs")
+        .join("home.json");
+    assert_eq!(config.unwrap().store, expected);
+}
+
+#[test]
+fn profile_names_must_be_file_names_without_a_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(
+        &path,
+        "[profiles.\"../../x\"]\n[profiles.\"a/b\"]\nstore = \"ab.json\"\n",
+    )
+    .unwrap();
+
+    let config = Config::load(Some(&path), Some("../../x"));
+    assert!(matches!(config, Err(ConfigError::ProfileName(name)) if name == "../../x"));
+    let config = Config::load(Some(&path), Some("a/b")).unwrap();
+    assert_eq!(config.store, dir.path().join("ab.json"));
+}
+
+#[test]
+fn missing_file_gives_the_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::load(Some(&dir.path().join("config.toml")), None).unwrap();
+    assert_eq!(config.format, OutputFormat::Text);
+    assert_eq!(config.sort, SortOrder::Title);
+    assert_eq!(config.date_format, "%Y-%m-%d %H:%M");
+    assert!(config.store.ends_with("memo-rs/memos.json"));
+}
@@ -0,0 +1,103 @@
+use memo_rs::{parse_tags, Error, Memo, Memos};
+
+fn memo(title: &str, text: &str) -> Memo {
+    Memo::new(title.to_owned(), text.to_owned())
+}
+
+#[test]
+fn add_rejects_duplicate_and_empty_titles() {
+    let mut memos = Memos::new();
+    memos.add(memo("a", "first")).unwrap();
+
+    assert!(
+        matches!(memos.add(memo("a", "second")), Err(Error::AlreadyExists(title)) if title == "a")
+    );
+    assert!(matches!(
+        memos.add(memo("", "text")),
+        Err(Error::EmptyTitle)
+    ));
+    assert_eq!(memos.get_one("a").unwrap().text, "first");
+    assert_eq!(memos.len(), 1);
+}
+
+#[test]
+fn update_bumps_edit_time() {
+    let mut memos = Memos::new();
+    memos.add(memo("a", "first")).unwrap();
+    let created = memos.get_one("a").unwrap().created_at;
+
+    memos.update("a", "second").unwrap();
+
+    let updated = memos.get_one("a").unwrap();
+    assert_eq!(updated.text, "second");
+    assert_eq!(updated.created_at, created);
+    assert!(updated.updated_at >= created);
+    assert!(matches!(memos.update("b", "x"), Err(Error::NotFound(_))));
+}
+
+#[test]
+fn modify_keeps_the_title() {
+    let mut memos = Memos::new();
+    memos.add(memo("a", "first")).unwrap();
+
+    memos
+        .modify("a", |memo| {
+            memo.title = "b".to_owned();
+            memo.archived = true;
+        })
+        .unwrap();
+
+    let modified = memos.get_one("a").unwrap();
+    assert_eq!(modified.title, "a");
+    assert!(modified.archived);
+    assert!(memos.get_one("b").is_none());
+}
+
+#[test]
+fn remove_returns_the_memo() {
+    let mut memos = Memos::new();
+    memos.add(memo("a", "first")).unwrap();
+
+    assert_eq!(memos.remove("a").unwrap().text, "first");
+    assert!(memos.is_empty());
+    assert!(matches!(memos.remove("a"), Err(Error::NotFound(_))));
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested").join("memos.json");
+
+    let mut memos = Memos::new();
+    let mut tagged = memo("a", "first");
+    tagged.tags = vec!["x".to_owned()];
+    tagged.notebook = Some("work".to_owned());
+    memos.add(tagged.clone()).unwrap();
+    memos.add(memo("b", "second")).unwrap();
+    memos.save(&path).unwrap();
+
+    let loaded = Memos::load(&path).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.get_one("a"), Some(&tagged));
+}
+
+#[test]
+fn load_missing_store_is_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(Memos::load(&dir.path().join("none.json"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn load_invalid_store_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memos.json");
+    std::fs::write(&path, "not json").unwrap();
+    assert!(matches!(Memos::load(&path), Err(Error::Store(_))));
+}
+
+#[test]
+fn parse_tags_drops_blanks() {
+    assert_eq!(parse_tags(" a, ,b ,"), vec!["a", "b"]);
+}
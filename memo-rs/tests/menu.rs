@@ -0,0 +1,105 @@
+use memo_rs::{Config, Memos, Menu};
+use tempfile::TempDir;
+
+fn config() -> (TempDir, Config) {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        store: dir.path().join("memos.json"),
+        ..Config::default()
+    };
+    (dir, config)
+}
+
+fn run(config: &Config, script: &str) -> String {
+    let mut menu = Menu::new(script.as_bytes(), Vec::new());
+    menu.run(config).unwrap();
+    String::from_utf8(menu.into_output()).unwrap()
+}
+
+#[test]
+fn add_then_view() {
+    let (_dir, config) = config();
+    let output = run(&config, "1\ngroceries\nmilk\nhome, food\nlife\n2\nq\n");
+
+    assert!(output.contains("Memo Added"));
+    assert!(output.contains("GoodBye"));
+
+    let memos = Memos::load(&config.store).unwrap();
+    let memo = memos.get_one("groceries").unwrap();
+    assert_eq!(memo.text, "milk");
+    assert_eq!(memo.tags, vec!["home", "food"]);
+    assert_eq!(memo.notebook.as_deref(), Some("life"));
+}
+
+#[test]
+fn optional_fields_can_be_skipped() {
+    let (_dir, config) = config();
+    run(&config, "1\ngroceries\nmilk\n\n\nq\n");
+
+    let memos = Memos::load(&config.store).unwrap();
+    let memo = memos.get_one("groceries").unwrap();
+    assert!(memo.tags.is_empty());
+    assert_eq!(memo.notebook, None);
+}
+
+#[test]
+fn duplicate_title_is_reported() {
+    let (_dir, config) = config();
+    let output = run(&config, "1\na\nfirst\n\n\n1\na\nsecond\n\n\nq\n");
+
+    assert!(output.contains("Memo already exists"));
+    let memos = Memos::load(&config.store).unwrap();
+    assert_eq!(memos.get_one("a").unwrap().text, "first");
+}
+
+#[test]
+fn update_and_remove() {
+    let (_dir, config) = config();
+    run(&config, "1\na\nfirst\n\n\n1\nb\nsecond\n\n\nq\n");
+
+    let output = run(&config, "4\na\nchanged\n3\nb\n3\nmissing\nq\n");
+    assert!(output.contains("Updated!"));
+    assert!(output.contains("Removed!"));
+    assert!(output.contains("Not found memo"));
+
+    let memos = Memos::load(&config.store).unwrap();
+    assert_eq!(memos.get_one("a").unwrap().text, "changed");
+    assert!(memos.get_one("b").is_none());
+}
+
+#[test]
+fn show_renders_markdown() {
+    let (_dir, config) = config();
+    run(&config, "1\nnote\n**bold** text\n\n\nq\n");
+
+    let output = run(&config, "6\nnote\nq\n");
+    assert!(output.contains("bold text"));
+    assert!(!output.contains("**"));
+}
+
+#[test]
+fn stats_and_invalid_choices() {
+    let (_dir, config) = config();
+    run(&config, "1\nnote\nthree little words\n\n\nq\n");
+
+    let output = run(&config, "5\n1\n9\n5\nabc\n5\n100000\nq\n");
+    assert!(output.contains("Memos:      1"));
+    assert!(output.contains("Words:      3"));
+    assert!(output.contains("Invalid command"));
+    assert_eq!(output.matches("Invalid number").count(), 2);
+}
+
+#[test]
+fn end_of_input_quits() {
+    let (_dir, config) = config();
+    let output = run(&config, "1\nnote\n");
+    assert!(!output.contains("Memo Added"));
+    assert!(Memos::load(&config.store).unwrap().is_empty());
+}
+
+#[test]
+fn viewing_does_not_write_the_store() {
+    let (_dir, config) = config();
+    run(&config, "2\n5\n\n9\n6\nmissing\nq\n");
+    assert!(!config.store.exists());
+}
@@ -0,0 +1,107 @@
+use memo_rs::repl::{split_args, Flow, Repl, ReplHelper};
+use memo_rs::{Config, Error, Memos};
+use tempfile::TempDir;
+
+fn config() -> (TempDir, Config) {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        store: dir.path().join("memos.json"),
+        ..Config::default()
+    };
+    (dir, config)
+}
+
+fn execute(repl: &mut Repl, line: &str) -> String {
+    let mut out = Vec::new();
+    assert_eq!(repl.execute(line, &mut out).unwrap(), Flow::Continue);
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn add_list_and_remove() {
+    let (_dir, config) = config();
+    let mut repl = Repl::new(&config).unwrap();
+
+    assert_eq!(
+        execute(&mut repl, "add \"weekly meeting\" agenda and notes"),
+        "Memo Added\n"
+    );
+    assert!(execute(&mut repl, "ls").contains("weekly meeting"));
+
+    let memos = Memos::load(&config.store).unwrap();
+    assert_eq!(
+        memos.get_one("weekly meeting").unwrap().text,
+        "agenda and notes"
+    );
+
+    assert_eq!(execute(&mut repl, "rm 'weekly meeting'"), "Removed!\n");
+    assert!(Memos::load(&config.store).unwrap().is_empty());
+}
+
+#[test]
+fn errors_leave_the_shell_running() {
+    let (_dir, config) = config();
+    let mut repl = Repl::new(&config).unwrap();
+    let mut out = Vec::new();
+
+    assert!(matches!(
+        repl.execute("rm missing", &mut out),
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        repl.execute("add only-title", &mut out),
+        Err(Error::Usage(_))
+    ));
+    assert!(matches!(
+        repl.execute("frobnicate", &mut out),
+        Err(Error::Usage(_))
+    ));
+    assert_eq!(repl.execute("", &mut out).unwrap(), Flow::Continue);
+    assert_eq!(repl.execute("quit", &mut out).unwrap(), Flow::Quit);
+}
+
+#[test]
+fn split_args_keeps_quoted_words() {
+    assert_eq!(
+        split_args(r#"add "a b" 'c "d"' e"#).unwrap(),
+        vec!["add", "a b", "c \"d\"", "e"]
+    );
+    assert_eq!(split_args("rm \"\"").unwrap(), vec!["rm", ""]);
+    assert!(split_args("rm \"open").is_err());
+}
+
+#[test]
+fn completes_commands_and_titles() {
+    let helper = ReplHelper {
+        titles: vec!["groceries".to_owned(), "weekly meeting".to_owned()],
+    };
+
+    assert_eq!(
+        helper.candidates("s", 1),
+        (0, vec!["show".to_owned(), "stats".to_owned()])
+    );
+    assert_eq!(
+        helper.candidates("rm g", 4),
+        (3, vec!["groceries".to_owned()])
+    );
+    assert_eq!(
+        helper.candidates("show \"we", 8),
+        (5, vec!["\"weekly meeting\"".to_owned()])
+    );
+    assert_eq!(helper.candidates("add g", 5), (4, vec![]));
+}
+
+#[test]
+fn completed_titles_split_back_to_the_title() {
+    let titles = ["it's \"done\"", "say \"hi\"", "don't", "plain"];
+    let helper = ReplHelper {
+        titles: titles.iter().map(|title| title.to_string()).collect(),
+    };
+
+    let (_, completions) = helper.candidates("show ", 5);
+    assert_eq!(completions.len(), titles.len());
+    for (title, completion) in titles.iter().zip(completions) {
+        let line = format!("show {}", completion);
+        assert_eq!(split_args(&line).unwrap(), vec!["show", title]);
+    }
+}
@@ -0,0 +1,93 @@
+use memo_rs::watch::{self, ChangeKind, StoreWatcher};
+use memo_rs::{Memo, Memos};
+use std::time::Duration;
+
+fn memo(title: &str, text: &str) -> Memo {
+    Memo::new(title.to_owned(), text.to_owned())
+}
+
+#[test]
+fn subscribers_receive_every_change() {
+    let mut memos = Memos::new();
+    let events = memos.subscribe();
+
+    memos.add(memo("a", "first")).unwrap();
+    memos.update("a", "second").unwrap();
+    memos.remove("a").unwrap();
+    assert!(memos.remove("a").is_err());
+
+    let kinds: Vec<_> = events
+        .try_iter()
+        .map(|event| (event.kind, event.title))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (ChangeKind::Added, "a".to_owned()),
+            (ChangeKind::Updated, "a".to_owned()),
+            (ChangeKind::Removed, "a".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn dropped_subscribers_are_forgotten() {
+    let mut memos = Memos::new();
+    drop(memos.subscribe());
+    memos.add(memo("a", "first")).unwrap();
+}
+
+#[test]
+fn diff_lists_added_updated_and_removed() {
+    let mut old = Memos::new();
+    old.add(memo("kept", "same")).unwrap();
+    old.add(memo("changed", "before")).unwrap();
+    old.add(memo("gone", "bye")).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memos.json");
+    old.save(&path).unwrap();
+    let mut new = Memos::load(&path).unwrap();
+    new.update("changed", "after").unwrap();
+    new.remove("gone").unwrap();
+    new.add(memo("fresh", "hi")).unwrap();
+
+    let events: Vec<_> = watch::diff(&old, &new)
+        .into_iter()
+        .map(|event| (event.kind, event.title))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            (ChangeKind::Updated, "changed".to_owned()),
+            (ChangeKind::Added, "fresh".to_owned()),
+            (ChangeKind::Removed, "gone".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn store_watcher_sees_saves_from_elsewhere() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memos.json");
+    let mut watcher = StoreWatcher::new(&path).unwrap();
+
+    let mut memos = Memos::new();
+    memos.add(memo("a", "first")).unwrap();
+    memos.save(&path).unwrap();
+
+    let events = watcher.wait(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, ChangeKind::Added);
+    assert_eq!(events[0].title, "a");
+
+    memos.remove("a").unwrap();
+    memos.save(&path).unwrap();
+    let events = watcher.wait(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events[0].kind, ChangeKind::Removed);
+
+    assert!(watcher
+        .wait(Some(Duration::from_millis(100)))
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,363 @@
+//! A background process that keeps the vault unlocked for a while, so the
+//! TUI and the commands ask for the master password once per session.
+//!
+//! Requests and responses are one line of JSON each, over a Unix socket
+//! that only the user running the agent can connect to.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::config::Config;
+use crate::entry::Password;
+use crate::store::save_merged;
+use crate::vault::{Vault, VaultError};
+
+// 응답이 없는 상대 때문에 에이전트가 멈추지 않게 한다.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AgentError {
+    #[error("agent connection failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid agent message: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Vault(#[from] VaultError),
+    #[error("an agent is already listening on {0}")]
+    Running(PathBuf),
+    #[error("refused connection from uid {0}")]
+    Peer(u32),
+    #[error("agent: {0}")]
+    Refused(String),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+enum Request {
+    Status,
+    Unlock {
+        master: Zeroizing<String>,
+    },
+    Load,
+    /// `passwords` were edited from `base`, what the client loaded.
+    Save {
+        base: Vec<Password>,
+        passwords: Vec<Password>,
+    },
+    Lock,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+enum Response {
+    Status { vault: PathBuf, unlocked: bool },
+    Passwords { passwords: Vec<Password> },
+    Done,
+    Error { message: String },
+}
+
+/// `agent.sock` in the user runtime directory.
+pub fn default_socket() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join("pass-manager").join("agent.sock"),
+        None => std::env::temp_dir()
+            .join(format!("pass-manager-{}", nix::unistd::getuid()))
+            .join("agent.sock"),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    use nix::sys::socket::{getsockopt, sockopt::PeerCredentials};
+    Ok(getsockopt(stream, PeerCredentials)?.uid())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    Ok(nix::unistd::getpeereid(stream)?.0.as_raw())
+}
+
+// 양쪽 모두 상대가 같은 사용자인지 확인한다.
+fn check_peer(stream: &UnixStream) -> Result<(), AgentError> {
+    let uid = peer_uid(stream)?;
+    match uid == nix::unistd::getuid().as_raw() {
+        true => Ok(()),
+        false => Err(AgentError::Peer(uid)),
+    }
+}
+
+fn send<T: Serialize>(stream: &mut UnixStream, message: &T) -> Result<(), AgentError> {
+    let mut line = Zeroizing::new(serde_json::to_vec(message)?);
+    line.push(b'\n');
+    stream.write_all(&line)?;
+    Ok(())
+}
+
+fn receive<T: for<'de> Deserialize<'de>>(stream: &UnixStream) -> Result<T, AgentError> {
+    let mut line = Zeroizing::new(String::new());
+    BufReader::new(stream).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+struct State {
+    // 요청을 처리하는 동안 잠그지 않도록 복사해 간다.
+    vault: Option<Arc<Vault>>,
+    last_used: Instant,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    // 읽고 합치고 쓰는 사이에 다른 저장이 끼어들지 않게 한다.
+    saving: Mutex<()>,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Runs the agent until it's killed. It starts locked; the first client
+/// sends the master password.
+pub fn serve(config: &Config) -> Result<(), AgentError> {
+    let socket = &config.agent_socket;
+    let running = Client {
+        socket: socket.to_owned(),
+        unlocked: false,
+    };
+    if running.request(&Request::Status).is_ok() {
+        return Err(AgentError::Running(socket.to_owned()));
+    }
+    if let Some(dir) = socket.parent() {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)?;
+    }
+    // 이전 에이전트가 남긴 소켓 파일
+    let _ = fs::remove_file(socket);
+    let listener = UnixListener::bind(socket)?;
+    fs::set_permissions(socket, fs::Permissions::from_mode(0o600))?;
+    eprintln!("Agent listening on {}", socket.display());
+
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            vault: None,
+            last_used: Instant::now(),
+        }),
+        saving: Mutex::new(()),
+    });
+    if let Some(timeout) = config.agent_timeout {
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_secs(1));
+            let mut state = shared.state();
+            if state.vault.is_some() && state.last_used.elapsed() >= timeout {
+                state.vault = None;
+                eprintln!("Locked after inactivity");
+            }
+        });
+    }
+
+    // 느린 클라이언트가 다른 클라이언트를 막지 않도록 연결마다 스레드를 쓴다.
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("{}", AgentError::from(e));
+                continue;
+            }
+        };
+        let (path, shared) = (config.vault.to_owned(), Arc::clone(&shared));
+        thread::spawn(move || {
+            if let Err(e) = handle(stream, &path, &shared) {
+                eprintln!("{}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle(mut stream: UnixStream, path: &Path, shared: &Shared) -> Result<(), AgentError> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    if let Err(e) = check_peer(&stream) {
+        let _ = send(&mut stream, &error("permission denied"));
+        return Err(e);
+    }
+
+    let request: Request = receive(&stream)?;
+    let response = respond(request, path, shared);
+    send(&mut stream, &response)
+}
+
+// 키 유도와 파일 입출력은 상태를 잠그지 않은 채로 한다.
+fn respond(request: Request, path: &Path, shared: &Shared) -> Response {
+    let vault = {
+        let mut state = shared.state();
+        if !matches!(request, Request::Status) {
+            state.last_used = Instant::now();
+        }
+        state.vault.clone()
+    };
+    match request {
+        Request::Status => Response::Status {
+            vault: path.to_owned(),
+            unlocked: vault.is_some(),
+        },
+        Request::Unlock { master } => match vault.map(|v| v.verify(&master)) {
+            Some(true) => Response::Done,
+            Some(false) => error(VaultError::WrongPassword),
+            None => match Vault::open::<Vec<Password>>(path, &master) {
+                Ok((vault, _)) => {
+                    shared.state().vault = Some(Arc::new(vault));
+                    Response::Done
+                }
+                Err(e) => error(e),
+            },
+        },
+        Request::Load => match vault.as_deref().map(Vault::load) {
+            Some(Ok(passwords)) => Response::Passwords { passwords },
+            Some(Err(e)) => error(e),
+            None => error("the vault is locked"),
+        },
+        Request::Save { base, passwords } => match vault {
+            Some(vault) => {
+                let _saving = shared.saving.lock().unwrap_or_else(|e| e.into_inner());
+                match save_merged(&vault, &base, &passwords) {
+                    Ok(passwords) => Response::Passwords { passwords },
+                    Err(e) => error(e),
+                }
+            }
+            None => error("the vault is locked"),
+        },
+        Request::Lock => {
+            shared.state().vault = None;
+            Response::Done
+        }
+    }
+}
+
+fn error(e: impl ToString) -> Response {
+    Response::Error {
+        message: e.to_string(),
+    }
+}
+
+/// A connection to a running agent that serves the same vault.
+pub struct Client {
+    socket: PathBuf,
+    pub unlocked: bool,
+}
+
+impl Client {
+    /// Finds an agent on `socket`. An agent for another vault is ignored.
+    pub fn connect(socket: &Path, vault: &Path) -> Option<Self> {
+        let client = Self {
+            socket: socket.to_owned(),
+            unlocked: false,
+        };
+        match client.request(&Request::Status) {
+            Ok(Response::Status {
+                vault: served,
+                unlocked,
+            }) if served == vault => Some(Self { unlocked, ..client }),
+            _ => None,
+        }
+    }
+
+    fn request(&self, request: &Request) -> Result<Response, AgentError> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        check_peer(&stream)?;
+        send(&mut stream, request)?;
+        match receive(&stream)? {
+            Response::Error { message } => Err(AgentError::Refused(message)),
+            response => Ok(response),
+        }
+    }
+
+    fn expect_done(&self, request: &Request) -> Result<(), AgentError> {
+        match self.request(request)? {
+            Response::Done => Ok(()),
+            _ => Err(AgentError::Refused("unexpected response".to_owned())),
+        }
+    }
+
+    /// Unlocks the agent, or checks the password when it's already unlocked.
+    pub fn unlock(&self, master: &str) -> Result<(), AgentError> {
+        self.expect_done(&Request::Unlock {
+            master: Zeroizing::new(master.to_owned()),
+        })
+    }
+
+    pub fn load(&self) -> Result<Vec<Password>, AgentError> {
+        match self.request(&Request::Load)? {
+            Response::Passwords { passwords } => Ok(passwords),
+            _ => Err(AgentError::Refused("unexpected response".to_owned())),
+        }
+    }
+
+    pub fn save(
+        &self,
+        base: &[Password],
+        passwords: &[Password],
+    ) -> Result<Vec<Password>, AgentError> {
+        let request = Request::Save {
+            base: base.to_vec(),
+            passwords: passwords.to_vec(),
+        };
+        match self.request(&request)? {
+            Response::Passwords { passwords } => Ok(passwords),
+            _ => Err(AgentError::Refused("unexpected response".to_owned())),
+        }
+    }
+
+    pub fn lock(&self) -> Result<(), AgentError> {
+        self.expect_done(&Request::Lock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    #[test]
+    fn requests_round_trip_as_json_lines() {
+        let request = Request::Unlock {
+            master: Zeroizing::new("pw".to_owned()),
+        };
+        let line = serde_json::to_string(&request).unwrap();
+        assert_eq!(line, r#"{"request":"unlock","master":"pw"}"#);
+        assert!(matches!(
+            serde_json::from_str(&line).unwrap(),
+            Request::Unlock { master } if *master == "pw"
+        ));
+    }
+
+    #[test]
+    fn peers_of_the_same_user_are_accepted() {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .subsec_nanos();
+        let path = std::env::temp_dir().join(format!("pass-manager-agent-{}.sock", nanos));
+        let listener = UnixListener::bind(&path).unwrap();
+        let client = UnixStream::connect(&path).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        assert!(check_peer(&client).is_ok());
+        assert!(check_peer(&server).is_ok());
+        fs::remove_file(&path).unwrap();
+    }
+}
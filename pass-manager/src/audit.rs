@@ -0,0 +1,242 @@
+//! The security report over the vault: strength of every password, reused
+//! passwords, weak ones, breached ones and the ones not changed for a long
+//! time.
+
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::breach::Breaches;
+use crate::entry::Password;
+use crate::generator;
+
+/// Passwords shorter than this are reported.
+pub const MIN_LENGTH: usize = 12;
+
+/// Estimates the entropy of a password from its length and the kinds of
+/// characters it uses, as if every character was picked at random.
+pub fn entropy(password: &str) -> f64 {
+    let has = |f: fn(&char) -> bool| password.chars().any(|c| f(&c));
+    let pool = [
+        (has(char::is_ascii_lowercase), 26),
+        (has(char::is_ascii_uppercase), 26),
+        (has(char::is_ascii_digit), 10),
+        (has(char::is_ascii_punctuation), 32),
+        (
+            has(|c| !c.is_ascii_alphanumeric() && !c.is_ascii_punctuation()),
+            100,
+        ),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum::<u32>();
+
+    match pool {
+        0 => 0.0,
+        pool => password.chars().count() as f64 * f64::from(pool).log2(),
+    }
+}
+
+/// What the audit found about one entry.
+pub struct Finding {
+    pub title: String,
+    pub bits: f64,
+    /// Titles of the other entries with the same password
+    pub reused_with: Vec<String>,
+    pub short: bool,
+    pub lowercase_only: bool,
+    /// Days since the password changed, `None` when that's not known
+    pub age: Option<i64>,
+    pub stale: bool,
+    /// Times the password was seen in breaches
+    pub breached: u64,
+}
+
+impl Finding {
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = vec![];
+        if self.breached > 0 {
+            issues.push(format!("seen {} times in breaches", self.breached));
+        }
+        if !self.reused_with.is_empty() {
+            issues.push(format!("reused in {}", self.reused_with.join(", ")));
+        }
+        if self.short {
+            issues.push("short".to_owned());
+        }
+        if self.lowercase_only {
+            issues.push("only lowercase letters".to_owned());
+        }
+        match self.age {
+            Some(age) if self.stale => issues.push(format!("not changed in {} days", age)),
+            Some(_) => {}
+            None => issues.push("age unknown".to_owned()),
+        }
+        issues
+    }
+}
+
+pub struct Report {
+    pub findings: Vec<Finding>,
+    pub max_age: u32,
+}
+
+impl Report {
+    /// Audits `passwords`, reporting the ones older than `max_age` days and
+    /// the ones in `breaches`. The findings are sorted with the breached
+    /// passwords first, then from the weakest password up.
+    pub fn new(
+        passwords: &[Password],
+        max_age: u32,
+        now: DateTime<Local>,
+        breaches: &Breaches,
+    ) -> Self {
+        // 제목이 같은 항목도 있으므로 위치로 구분한다.
+        let mut by_password: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, entry) in passwords.iter().enumerate() {
+            by_password.entry(&entry.password).or_default().push(index);
+        }
+
+        let mut findings: Vec<Finding> = passwords
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let age = match entry.changed_known() {
+                    true => Some((now - entry.changed_at).num_days()),
+                    false => None,
+                };
+                Finding {
+                    title: entry.title.to_owned(),
+                    bits: entropy(&entry.password),
+                    reused_with: by_password[entry.password.as_str()]
+                        .iter()
+                        .filter(|other| **other != index)
+                        .map(|other| passwords[*other].title.to_owned())
+                        .collect(),
+                    short: entry.password.chars().count() < MIN_LENGTH,
+                    lowercase_only: !entry.password.is_empty()
+                        && entry.password.chars().all(|c| c.is_lowercase()),
+                    age,
+                    stale: age.is_some_and(|age| age > i64::from(max_age)),
+                    breached: breaches.count(&entry.password),
+                }
+            })
+            .collect();
+        findings.sort_by(|a, b| {
+            (b.breached > 0)
+                .cmp(&(a.breached > 0))
+                .then(a.bits.total_cmp(&b.bits))
+                .then(a.title.cmp(&b.title))
+        });
+
+        Self { findings, max_age }
+    }
+
+    pub fn issue_count(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| !finding.issues().is_empty())
+            .count()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let width = self
+            .findings
+            .iter()
+            .map(|finding| finding.title.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for finding in &self.findings {
+            let _ = write!(
+                text,
+                "{:width$}  {:>4.0} bits  {:11}",
+                finding.title,
+                finding.bits,
+                generator::strength(finding.bits),
+                width = width
+            );
+            let issues = finding.issues();
+            if !issues.is_empty() {
+                let _ = write!(text, "  {}", issues.join("; "));
+            }
+            text.push('\n');
+        }
+        let _ = writeln!(
+            text,
+            "\n{} of {} entries need attention (max age {} days)",
+            self.issue_count(),
+            self.findings.len(),
+            self.max_age
+        );
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(title: &str, password: &str, age: i64, now: DateTime<Local>) -> Password {
+        let mut entry = Password::new(title.to_owned(), "me".to_owned(), password.to_owned());
+        entry.changed_at = now - Duration::days(age);
+        entry
+    }
+
+    #[test]
+    fn entropy_counts_the_character_pool() {
+        assert_eq!(entropy(""), 0.0);
+        assert!((entropy("abcd") - 4.0 * 26f64.log2()).abs() < 1e-9);
+        assert!((entropy("aB3!") - 4.0 * 94f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn finds_reused_weak_and_stale_passwords() {
+        let now = Local::now();
+        let passwords = vec![
+            entry("mail", "correcthorse", 10, now),
+            entry("bank", "correcthorse", 400, now),
+            entry("work", "x7#Kq9!zLm2$Vb", 10, now),
+            entry("forum", "hunter2", 10, now),
+        ];
+        let report = Report::new(&passwords, 365, now, &Breaches::default());
+        let finding = |title| {
+            report
+                .findings
+                .iter()
+                .find(|finding| finding.title == title)
+                .unwrap()
+        };
+
+        assert_eq!(finding("mail").reused_with, vec!["bank"]);
+        assert!(finding("mail").lowercase_only);
+        assert!(!finding("mail").stale);
+        assert!(finding("bank").stale);
+        assert!(finding("forum").short);
+        assert!(!finding("forum").lowercase_only);
+        assert!(finding("work").issues().is_empty());
+        assert_eq!(report.issue_count(), 3);
+        assert_eq!(report.findings[0].title, "forum");
+    }
+
+    #[test]
+    fn same_titles_and_unknown_ages() {
+        let now = Local::now();
+        let mut passwords = vec![
+            entry("mail", "x7#Kq9!zLm2$Vb", 10, now),
+            entry("mail", "x7#Kq9!zLm2$Vb", 10, now),
+        ];
+        passwords[1].changed_at = crate::entry::unknown_date();
+        let report = Report::new(&passwords, 365, now, &Breaches::default());
+
+        for finding in &report.findings {
+            assert_eq!(finding.reused_with, vec!["mail"]);
+            assert!(!finding.stale);
+        }
+        let unknown = report.findings.iter().find(|f| f.age.is_none()).unwrap();
+        assert!(unknown.issues().contains(&"age unknown".to_owned()));
+    }
+}
@@ -0,0 +1,183 @@
+//! Checks passwords against a local copy of the Have I Been Pwned password
+//! list, so nothing leaves the machine.
+//!
+//! The list is the file of SHA-1 hashes sorted by hash, one `HASH:COUNT`
+//! line per password, as made by the official downloader. It's too big to
+//! load, so lookups binary search the file.
+
+use sha1::{Digest, Sha1};
+use std::collections::hash_map::{Entry, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::entry::Password;
+
+const HASH_LEN: usize = 40;
+
+type Hash = [u8; 20];
+
+fn sha1(password: &str) -> Hash {
+    Sha1::digest(password.as_bytes()).into()
+}
+
+fn hex(hash: &Hash) -> String {
+    hash.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+pub struct BreachList {
+    reader: BufReader<File>,
+    len: u64,
+}
+
+impl BreachList {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            reader: BufReader::new(file),
+            len,
+        })
+    }
+
+    /// How many times the password with this hash was seen in breaches,
+    /// 0 if it's not in the list.
+    fn count(&mut self, hash: &Hash) -> io::Result<u64> {
+        let target = hex(hash);
+        // lo는 항상 줄의 시작이고, 찾는 줄은 [lo, hi)에서 시작한다.
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = self.line_start(mid)?;
+            if start >= hi {
+                hi = mid;
+                continue;
+            }
+            let line = self.line_at(start)?;
+            let hash = line.get(..HASH_LEN).unwrap_or(&line).to_ascii_uppercase();
+            match hash.as_str().cmp(target.as_str()) {
+                std::cmp::Ordering::Equal => {
+                    let count = line.trim_end().rsplit_once(':').map(|(_, count)| count);
+                    return Ok(count.and_then(|c| c.parse().ok()).unwrap_or(1));
+                }
+                std::cmp::Ordering::Less => lo = start + line.len() as u64,
+                std::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+        Ok(0)
+    }
+
+    // pos가 줄 중간이면 다음 줄의 시작
+    fn line_start(&mut self, pos: u64) -> io::Result<u64> {
+        if pos == 0 {
+            return Ok(0);
+        }
+        self.reader.seek(SeekFrom::Start(pos - 1))?;
+        let mut skipped = vec![];
+        let read = self.reader.read_until(b'\n', &mut skipped)?;
+        Ok(pos - 1 + read as u64)
+    }
+
+    // 줄 끝의 개행 문자까지 포함한다.
+    fn line_at(&mut self, pos: u64) -> io::Result<String> {
+        self.reader.seek(SeekFrom::Start(pos))?;
+        let mut line = String::new();
+        self.reader.read_line(&mut line)?;
+        Ok(line)
+    }
+}
+
+/// The breach counts of the passwords checked so far.
+#[derive(Default)]
+pub struct Breaches {
+    counts: HashMap<Hash, u64>,
+}
+
+impl Breaches {
+    /// Looks up the passwords not checked yet.
+    pub fn update(&mut self, list: &mut BreachList, passwords: &[Password]) -> io::Result<()> {
+        for entry in passwords {
+            let hash = sha1(&entry.password);
+            if let Entry::Vacant(vacant) = self.counts.entry(hash) {
+                vacant.insert(list.count(&hash)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Times the password was seen in breaches, 0 when it wasn't or it
+    /// wasn't checked.
+    pub fn count(&self, password: &str) -> u64 {
+        if self.counts.is_empty() {
+            return 0;
+        }
+        self.counts.get(&sha1(password)).copied().unwrap_or(0)
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn list(lines: &[&str], name: &str) -> (BreachList, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!(
+            "pass-manager-breach-{}-{}.txt",
+            name,
+            std::process::id()
+        ));
+        let mut file = File::create(&path).unwrap();
+        for line in lines {
+            write!(file, "{}\r\n", line).unwrap();
+        }
+        (BreachList::open(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn binary_searches_the_sorted_list() {
+        // sha1("password")는 5BAA61E4...이다.
+        let mut hashes = vec![
+            "0000000A0E3B9F25FF41DE4B5AC238C2D545C7A8:15".to_owned(),
+            "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9659365".to_owned(),
+            "FFFFFFF8A0382AA9C8D9536EFBA77F261815334D:12".to_owned(),
+        ];
+        for i in 0..200 {
+            hashes.push(format!("{:040X}:{}", i * 7919, i));
+        }
+        hashes.sort();
+        let lines: Vec<&str> = hashes.iter().map(String::as_str).collect();
+        let (mut list, path) = list(&lines, "search");
+
+        assert_eq!(list.count(&sha1("password")).unwrap(), 9659365);
+        assert_eq!(list.count(&sha1("not in the list")).unwrap(), 0);
+        let first = [
+            0, 0, 0, 0x0a, 0x0e, 0x3b, 0x9f, 0x25, 0xff, 0x41, 0xde, 0x4b, 0x5a, 0xc2, 0x38, 0xc2,
+            0xd5, 0x45, 0xc7, 0xa8,
+        ];
+        assert_eq!(list.count(&first).unwrap(), 15);
+        for i in 0..200u64 {
+            let mut hash = [0; 20];
+            hash[12..].copy_from_slice(&(i * 7919).to_be_bytes());
+            assert_eq!(list.count(&hash).unwrap(), i);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn breaches_cache_the_lookups() {
+        let (mut list, path) = list(&["5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:3"], "cache");
+        let passwords = vec![
+            Password::new("a".to_owned(), "me".to_owned(), "password".to_owned()),
+            Password::new("b".to_owned(), "me".to_owned(), "x7#Kq9!zLm2$Vb".to_owned()),
+        ];
+        let mut breaches = Breaches::default();
+        breaches.update(&mut list, &passwords).unwrap();
+        assert_eq!(breaches.count("password"), 3);
+        assert_eq!(breaches.count("x7#Kq9!zLm2$Vb"), 0);
+        std::fs::remove_file(path).unwrap();
+    }
+}
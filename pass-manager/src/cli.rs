@@ -0,0 +1,383 @@
+//! The commands that run without the TUI, for scripts.
+
+use chrono::Local;
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::io::{BufRead, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+#[cfg(unix)]
+use crate::agent::{self, Client};
+use crate::audit::Report;
+use crate::breach::{BreachList, Breaches};
+use crate::config::Config;
+use crate::entry::{self, Filter, Password};
+use crate::generator::{self, Options};
+use crate::import::{self, MapArg};
+use crate::store::Store;
+use crate::totp::Totp;
+use crate::vault::Vault;
+
+#[derive(Parser)]
+#[command(author, version, about = "A terminal password manager")]
+pub struct Cli {
+    /// Read the master password from this file descriptor instead of asking
+    #[arg(long, global = true, value_name = "FD")]
+    pub password_fd: Option<u32>,
+    #[command(subcommand)]
+    pub cmd: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print a security report of the vault
+    Audit {
+        /// Report passwords not changed in this many days (defaults to the config file)
+        #[arg(long)]
+        days: Option<u32>,
+        /// Sorted Have I Been Pwned hash list (defaults to the config file)
+        #[arg(long, value_name = "FILE")]
+        breach_list: Option<PathBuf>,
+    },
+    /// Import a CSV export of another password manager
+    Import {
+        file: PathBuf,
+        /// bitwarden, 1password, keepass, chrome or generic (guessed from the header row)
+        #[arg(long)]
+        format: Option<import::Format>,
+        /// Read a field from another column, e.g. --map title=Site
+        #[arg(long, value_name = "FIELD=COLUMN")]
+        map: Vec<MapArg>,
+    },
+    /// Print one field of an entry
+    Get {
+        title: String,
+        /// password, username, url, notes, totp (the current code) or a custom field
+        #[arg(long, default_value = "password")]
+        field: String,
+    },
+    /// Add an entry. The password is read from stdin, or asked for on a terminal
+    Add {
+        title: String,
+        #[arg(long, default_value = "")]
+        username: String,
+        #[arg(long, default_value = "")]
+        url: String,
+        #[arg(long, default_value = "")]
+        notes: String,
+        #[arg(long, default_value = "")]
+        folder: String,
+        /// Tag the entry, can be repeated
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Generate the password instead of reading it
+        #[arg(long)]
+        generate: bool,
+    },
+    /// List the entries, without their passwords
+    List {
+        #[arg(long)]
+        json: bool,
+        /// Only the entries in this folder
+        #[arg(long)]
+        folder: Option<String>,
+        /// Only the entries with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Print a new random password or passphrase
+    Generate(GenerateArgs),
+    /// Keep the vault unlocked for the TUI and the other commands
+    #[cfg(unix)]
+    Agent,
+    /// Make the agent forget the vault
+    #[cfg(unix)]
+    Lock,
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    #[arg(long, default_value_t = 20)]
+    length: usize,
+    /// Make a diceware passphrase of this many words instead
+    #[arg(long)]
+    words: Option<usize>,
+    #[arg(long, default_value_t = '-')]
+    separator: char,
+    #[arg(long)]
+    no_uppercase: bool,
+    #[arg(long)]
+    no_digits: bool,
+    #[arg(long)]
+    no_symbols: bool,
+    /// Leave out characters like l, 1 and O
+    #[arg(long)]
+    exclude_ambiguous: bool,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CliError {
+    #[error("no entry titled {0:?}")]
+    NotFound(String),
+    #[error("{1} entries are titled {0:?}")]
+    Ambiguous(String, usize),
+    #[error("{0:?} has no field {1:?}")]
+    NoField(String, String),
+    #[error("an entry titled {0:?} already exists")]
+    Exists(String),
+    #[error("the password is empty")]
+    EmptyPassword,
+}
+
+#[derive(Serialize)]
+struct ListItem<'a> {
+    title: &'a str,
+    username: &'a str,
+    url: &'a str,
+    folder: &'a str,
+    tags: &'a [String],
+}
+
+/// Where the master password comes from.
+pub struct Master {
+    fd: Option<u32>,
+}
+
+impl Master {
+    pub fn new(fd: Option<u32>) -> Self {
+        Self { fd }
+    }
+
+    /// Reads the first line of the file descriptor, or asks on the terminal.
+    pub fn read(&self) -> Result<Zeroizing<String>, Box<dyn Error>> {
+        let fd = match self.fd {
+            Some(fd) => fd,
+            None => {
+                return Ok(Zeroizing::new(rpassword::prompt_password(
+                    "Master password: ",
+                )?))
+            }
+        };
+        // 쓰는 쪽이 fd를 닫지 않아도 멈추지 않게 첫 줄만 읽는다.
+        let mut line = Zeroizing::new(String::new());
+        BufReader::new(fs::File::open(format!("/dev/fd/{}", fd))?).read_line(&mut line)?;
+        let password = line.trim_end_matches(['\n', '\r']);
+        Ok(Zeroizing::new(password.to_owned()))
+    }
+
+    /// Opens the vault through the agent when one is running, asking for
+    /// the master password only if the agent is locked.
+    fn open(&self, config: &Config) -> Result<(Store, Vec<Password>), Box<dyn Error>> {
+        #[cfg(unix)]
+        if let Some(client) = Client::connect(&config.agent_socket, &config.vault) {
+            if !client.unlocked {
+                client.unlock(&self.read()?)?;
+            }
+            let passwords = client.load()?;
+            return Ok((Store::Agent(client), passwords));
+        }
+        let master = self.read()?;
+        let (vault, passwords) = Vault::open(&config.vault, &master)?;
+        Ok((Store::Local(vault), passwords))
+    }
+}
+
+pub fn run(cmd: Command, config: &Config, master: &Master) -> Result<(), Box<dyn Error>> {
+    match cmd {
+        Command::Audit { days, breach_list } => audit(
+            config,
+            master,
+            days,
+            breach_list.or(config.breach_list.clone()),
+        ),
+        Command::Import { file, format, map } => import_file(config, master, &file, format, &map),
+        Command::Get { title, field } => get(config, master, &title, &field),
+        Command::Add {
+            title,
+            username,
+            url,
+            notes,
+            folder,
+            tag,
+            generate,
+        } => {
+            let (vault, mut passwords) = master.open(config)?;
+            if passwords.iter().any(|entry| entry.title == title) {
+                return Err(CliError::Exists(title).into());
+            }
+            let password = match generate {
+                true => Zeroizing::new(Options::default().generate()),
+                false => read_password(&title)?,
+            };
+            let mut entry = Password::new(title, username, password.to_string());
+            entry.url = url;
+            entry.notes = notes;
+            entry.folder = folder;
+            entry.tags = entry::parse_tags(&tag.join(","));
+            let base = passwords.clone();
+            passwords.push(entry);
+            vault.save(&base, &passwords)?;
+            Ok(())
+        }
+        Command::List { json, folder, tag } => {
+            let (_, passwords) = master.open(config)?;
+            let filters: Vec<Filter> = folder
+                .map(Filter::Folder)
+                .into_iter()
+                .chain(tag.map(Filter::Tag))
+                .collect();
+            let shown = passwords
+                .iter()
+                .filter(|entry| filters.iter().all(|filter| filter.matches(entry)));
+            if json {
+                let items: Vec<ListItem> = shown
+                    .map(|entry| ListItem {
+                        title: &entry.title,
+                        username: &entry.username,
+                        url: &entry.url,
+                        folder: &entry.folder,
+                        tags: &entry.tags,
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&items)?);
+            } else {
+                for entry in shown {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        entry.title, entry.username, entry.url, entry.folder
+                    );
+                }
+            }
+            Ok(())
+        }
+        Command::Generate(args) => {
+            let mut options = Options {
+                length: args
+                    .length
+                    .clamp(generator::MIN_LENGTH, generator::MAX_LENGTH),
+                uppercase: !args.no_uppercase,
+                digits: !args.no_digits,
+                symbols: !args.no_symbols,
+                exclude_ambiguous: args.exclude_ambiguous,
+                separator: args.separator,
+                ..Options::default()
+            };
+            if let Some(words) = args.words {
+                options.style = generator::Style::Passphrase;
+                options.words = words.clamp(generator::MIN_WORDS, generator::MAX_WORDS);
+            }
+            println!("{}", options.generate());
+            Ok(())
+        }
+        #[cfg(unix)]
+        Command::Agent => Ok(agent::serve(config)?),
+        #[cfg(unix)]
+        Command::Lock => match Client::connect(&config.agent_socket, &config.vault) {
+            Some(client) => Ok(client.lock()?),
+            None => Err("no agent is running".into()),
+        },
+    }
+}
+
+/// Prints the audit report.
+fn audit(
+    config: &Config,
+    master: &Master,
+    days: Option<u32>,
+    breach_list: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let (_, passwords) = master.open(config)?;
+    let mut breaches = Breaches::default();
+    if let Some(path) = breach_list {
+        let mut list = BreachList::open(&path)
+            .map_err(|e| format!("cannot read the breach list {}: {}", path.display(), e))?;
+        breaches.update(&mut list, &passwords)?;
+    }
+    let report = Report::new(
+        &passwords,
+        days.unwrap_or(config.max_password_age),
+        Local::now(),
+        &breaches,
+    );
+    print!("{}", report.to_text());
+    Ok(())
+}
+
+/// Imports a CSV file into the vault.
+fn import_file(
+    config: &Config,
+    master: &Master,
+    file: &Path,
+    format: Option<import::Format>,
+    map: &[MapArg],
+) -> Result<(), Box<dyn Error>> {
+    let imported = import::read_file(file, format, map)?;
+    let (vault, mut passwords) = master.open(config)?;
+    let base = passwords.clone();
+    let merged = import::merge(&mut passwords, imported.passwords);
+    vault.save(&base, &passwords)?;
+    println!("{} ({})", merged.summary(), imported.format.name());
+    Ok(())
+}
+
+fn get(config: &Config, master: &Master, title: &str, field: &str) -> Result<(), Box<dyn Error>> {
+    let (_, passwords) = master.open(config)?;
+    // 제목이 정확히 같은 항목을 먼저 찾고, 없으면 대소문자를 무시한다.
+    let mut found: Vec<&Password> = passwords.iter().filter(|e| e.title == title).collect();
+    if found.is_empty() {
+        found = passwords
+            .iter()
+            .filter(|e| e.title.to_lowercase() == title.to_lowercase())
+            .collect();
+    }
+    let entry = match found.as_slice() {
+        [entry] => entry,
+        [] => return Err(CliError::NotFound(title.to_owned()).into()),
+        _ => return Err(CliError::Ambiguous(title.to_owned(), found.len()).into()),
+    };
+
+    let value = match field {
+        "password" => entry.password.to_owned(),
+        "username" => entry.username.to_owned(),
+        "url" => entry.url.to_owned(),
+        "notes" => entry.notes.to_owned(),
+        "totp" if !entry.totp.is_empty() => Totp::parse(&entry.totp)?.now().0,
+        _ => entry
+            .custom
+            .iter()
+            .find(|custom| custom.name == field)
+            .map(|custom| custom.value.to_owned())
+            .ok_or_else(|| CliError::NoField(entry.title.to_owned(), field.to_owned()))?,
+    };
+    let value = Zeroizing::new(value);
+    println!("{}", value.as_str());
+    Ok(())
+}
+
+// 스크립트에서는 표준 입력의 첫 줄을 쓰고, 터미널이면 두 번 묻는다.
+fn read_password(title: &str) -> Result<Zeroizing<String>, Box<dyn Error>> {
+    let password = if std::io::stdin().is_terminal() {
+        let first = Zeroizing::new(rpassword::prompt_password(format!(
+            "Password for {}: ",
+            title
+        ))?);
+        let again = Zeroizing::new(rpassword::prompt_password("Repeat it: ")?);
+        if first != again {
+            return Err("the passwords don't match".into());
+        }
+        first
+    } else {
+        let mut line = Zeroizing::new(String::new());
+        std::io::stdin().lock().read_line(&mut line)?;
+        Zeroizing::new(line.trim_end_matches(['\n', '\r']).to_owned())
+    };
+    if password.is_empty() {
+        return Err(CliError::EmptyPassword.into());
+    }
+    Ok(password)
+}
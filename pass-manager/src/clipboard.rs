@@ -0,0 +1,74 @@
+use arboard::Clipboard;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// The system clipboard, cleared again some time after a secret is copied.
+pub struct SecretClipboard {
+    // 리눅스에서는 Clipboard가 살아 있는 동안만 복사한 내용이 유지된다.
+    clipboard: Option<Clipboard>,
+    timeout: Option<Duration>,
+    secret: Option<(Zeroizing<String>, Instant)>,
+}
+
+impl SecretClipboard {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            clipboard: None,
+            timeout,
+            secret: None,
+        }
+    }
+
+    fn clipboard(&mut self) -> Result<&mut Clipboard, arboard::Error> {
+        if self.clipboard.is_none() {
+            self.clipboard = Some(Clipboard::new()?);
+        }
+        Ok(self.clipboard.as_mut().unwrap())
+    }
+
+    /// Copies `text`. A secret is cleared after the timeout.
+    pub fn copy(&mut self, text: &str, secret: bool) -> Result<(), arboard::Error> {
+        self.clipboard()?.set_text(text)?;
+        self.secret = match (secret, self.timeout) {
+            // 시각이 넘칠 만큼 긴 시간이면 지우지 않는다.
+            (true, Some(timeout)) => Instant::now()
+                .checked_add(timeout)
+                .map(|deadline| (Zeroizing::new(text.to_owned()), deadline)),
+            _ => None,
+        };
+        Ok(())
+    }
+
+    /// Time left before the copied secret is cleared.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.secret
+            .as_ref()
+            .map(|(_, deadline)| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Clears the copied secret now instead of waiting for the timeout.
+    pub fn expire(&mut self) {
+        if let Some((_, deadline)) = self.secret.as_mut() {
+            *deadline = Instant::now();
+        }
+        self.tick();
+    }
+
+    /// Clears the copied secret once its time is up, unless something else
+    /// was copied over it in the meantime. Returns whether it was cleared.
+    pub fn tick(&mut self) -> bool {
+        match &self.secret {
+            Some((_, deadline)) if Instant::now() >= *deadline => {}
+            _ => return false,
+        }
+        let (secret, _) = self.secret.take().unwrap();
+        let clipboard = match self.clipboard() {
+            Ok(clipboard) => clipboard,
+            Err(_) => return false,
+        };
+        match clipboard.get_text().map(Zeroizing::new) {
+            Ok(text) if text == secret => clipboard.clear().is_ok(),
+            _ => false,
+        }
+    }
+}
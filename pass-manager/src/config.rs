@@ -0,0 +1,170 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+#[cfg(unix)]
+use crate::agent;
+use crate::keys::{Bindings, KeyError, KeyList};
+use crate::vault::Vault;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read {0}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("invalid config {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("invalid key bindings in {0}: {1}")]
+    Keys(PathBuf, KeyError),
+    #[error("{1} in {0} is longer than a year")]
+    Timeout(PathBuf, &'static str),
+}
+
+/// The longest timeout accepted, in seconds.
+const MAX_TIMEOUT: u64 = 365 * 24 * 60 * 60;
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    vault: Option<PathBuf>,
+    /// Seconds before a copied password is cleared, 0 keeps it
+    clipboard_timeout: u64,
+    /// Days after which the audit reports a password as not changed
+    max_password_age: u32,
+    /// Seconds of inactivity before the vault locks, 0 never locks
+    lock_timeout: u64,
+    /// Old passwords kept per entry
+    history_depth: usize,
+    // 같은 설정 파일을 다른 플랫폼에서도 쓸 수 있도록 받기는 한다.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    agent_socket: Option<PathBuf>,
+    /// Seconds the agent keeps the vault unlocked after the last request, 0 forever
+    #[cfg_attr(not(unix), allow(dead_code))]
+    agent_timeout: u64,
+    /// Sorted Have I Been Pwned hash list to check the passwords against
+    breach_list: Option<PathBuf>,
+    /// Keys of the TUI actions, by action name
+    keys: HashMap<String, KeyList>,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            vault: None,
+            clipboard_timeout: 30,
+            max_password_age: 365,
+            lock_timeout: 300,
+            history_depth: 10,
+            agent_socket: None,
+            agent_timeout: 900,
+            breach_list: None,
+            keys: HashMap::new(),
+        }
+    }
+}
+
+/// Settings from `config.toml`, with defaults for anything left out.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub vault: PathBuf,
+    /// `None` keeps copied passwords in the clipboard
+    pub clipboard_timeout: Option<Duration>,
+    pub max_password_age: u32,
+    /// `None` never locks the vault
+    pub lock_timeout: Option<Duration>,
+    pub history_depth: usize,
+    #[cfg(unix)]
+    pub agent_socket: PathBuf,
+    /// `None` keeps the agent unlocked until it's told to lock
+    #[cfg(unix)]
+    pub agent_timeout: Option<Duration>,
+    /// `None` skips the breach check
+    pub breach_list: Option<PathBuf>,
+    pub keys: Bindings,
+}
+
+impl Config {
+    /// The config path, `$PASS_MANAGER_CONFIG` or `config.toml` in the user config directory.
+    pub fn default_path() -> PathBuf {
+        match std::env::var_os("PASS_MANAGER_CONFIG") {
+            Some(path) => PathBuf::from(path),
+            None => dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("pass-manager")
+                .join("config.toml"),
+        }
+    }
+
+    /// Reads the config file at `path`. A missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let file: ConfigFile = match fs::read_to_string(path) {
+            Ok(buffer) => {
+                toml::from_str(&buffer).map_err(|e| ConfigError::Parse(path.to_owned(), e))?
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => ConfigFile::default(),
+            Err(e) => return Err(ConfigError::Io(path.to_owned(), e)),
+        };
+
+        // 상대 경로는 설정 파일 기준이다.
+        let relative = |file: PathBuf| match file.is_relative() {
+            true => path.parent().unwrap_or(Path::new(".")).join(file),
+            false => file,
+        };
+        // 환경 변수로 지정한 금고가 설정 파일보다 우선한다.
+        let vault = match (std::env::var_os("PASS_MANAGER_VAULT"), file.vault) {
+            (Some(path), _) => PathBuf::from(path),
+            (None, Some(vault)) => relative(vault),
+            (None, None) => Vault::default_path(),
+        };
+
+        // 0은 끄는 값이다.
+        let timeout = |seconds: u64, name: &'static str| match seconds {
+            0 => Ok(None),
+            seconds if seconds > MAX_TIMEOUT => Err(ConfigError::Timeout(path.to_owned(), name)),
+            seconds => Ok(Some(Duration::from_secs(seconds))),
+        };
+
+        Ok(Self {
+            vault,
+            clipboard_timeout: timeout(file.clipboard_timeout, "clipboard_timeout")?,
+            max_password_age: file.max_password_age,
+            lock_timeout: timeout(file.lock_timeout, "lock_timeout")?,
+            history_depth: file.history_depth,
+            #[cfg(unix)]
+            agent_socket: match std::env::var_os("PASS_MANAGER_AGENT") {
+                Some(socket) => PathBuf::from(socket),
+                None => file.agent_socket.unwrap_or_else(agent::default_socket),
+            },
+            #[cfg(unix)]
+            agent_timeout: timeout(file.agent_timeout, "agent_timeout")?,
+            breach_list: file.breach_list.map(relative),
+            keys: Bindings::new(&file.keys).map_err(|e| ConfigError::Keys(path.to_owned(), e))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_timeouts_are_refused() {
+        let path =
+            std::env::temp_dir().join(format!("pass-manager-config-{}.toml", std::process::id()));
+        fs::write(&path, "clipboard_timeout = 9223372036854775807\n").unwrap();
+        let result = Config::load(&path);
+        assert!(matches!(
+            result,
+            Err(ConfigError::Timeout(_, "clipboard_timeout"))
+        ));
+
+        fs::write(&path, "clipboard_timeout = 0\nlock_timeout = 60\n").unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.clipboard_timeout, None);
+        assert_eq!(config.lock_timeout, Some(Duration::from_secs(60)));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// An extra `name: value` pair on an entry. Secret values are masked like
+/// the password.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct CustomField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// A password the entry used before.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct PastPassword {
+    pub password: String,
+    /// When it was set
+    #[zeroize(skip)]
+    pub changed_at: DateTime<Local>,
+    /// When it was replaced
+    #[zeroize(skip)]
+    pub replaced_at: DateTime<Local>,
+}
+
+// 잠글 때와 복사본이 버려질 때 메모리에서 지운다.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct Password {
+    pub title: String,
+    pub username: String,
+    pub password: String,
+    /// 2FA secret, in base32 or as an `otpauth://` URI
+    #[serde(default)]
+    pub totp: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub custom: Vec<CustomField>,
+    /// Empty for entries outside of any folder
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Previous passwords, the most recent first
+    #[serde(default)]
+    pub history: Vec<PastPassword>,
+    // 읽을 때마다 바뀌지 않도록 없는 날짜는 unknown_date로 채운다.
+    #[serde(default = "unknown_date")]
+    #[zeroize(skip)]
+    pub created_at: DateTime<Local>,
+    #[serde(default = "unknown_date")]
+    #[zeroize(skip)]
+    pub updated_at: DateTime<Local>,
+    /// When the password last changed, [`unknown_date`] for entries saved
+    /// before it was recorded
+    #[serde(default = "unknown_date")]
+    #[zeroize(skip)]
+    pub changed_at: DateTime<Local>,
+}
+
+/// Stands in for the dates a vault doesn't have.
+pub fn unknown_date() -> DateTime<Local> {
+    DateTime::<Utc>::UNIX_EPOCH.with_timezone(&Local)
+}
+
+impl Password {
+    pub fn new(title: String, username: String, password: String) -> Self {
+        let now = Local::now();
+        Self {
+            title,
+            username,
+            password,
+            totp: String::new(),
+            url: String::new(),
+            notes: String::new(),
+            custom: vec![],
+            folder: String::new(),
+            tags: vec![],
+            history: vec![],
+            created_at: now,
+            updated_at: now,
+            changed_at: now,
+        }
+    }
+
+    /// Whether it's known when the password last changed.
+    pub fn changed_known(&self) -> bool {
+        self.changed_at != unknown_date()
+    }
+
+    /// Makes this entry the new version of `old`: keeps its creation date
+    /// and history, and adds the old password to the history if it changed.
+    /// At most `depth` old passwords are kept.
+    pub fn replace(&mut self, old: &Password, depth: usize) {
+        self.created_at = old.created_at;
+        self.history = old.history.clone();
+        if old.password == self.password {
+            self.changed_at = old.changed_at;
+        } else if !old.password.is_empty() {
+            self.history.insert(
+                0,
+                PastPassword {
+                    password: old.password.to_owned(),
+                    changed_at: old.changed_at,
+                    replaced_at: self.changed_at,
+                },
+            );
+        }
+        self.history.truncate(depth);
+    }
+}
+
+/// Applies the changes made elsewhere, from `base` to `theirs`, to `ours`,
+/// which was edited from `base` too. Entries they removed are removed,
+/// entries they added or changed are appended. When both sides changed the
+/// same entry, both versions are kept.
+pub fn merge(base: &[Password], ours: &[Password], theirs: &[Password]) -> Vec<Password> {
+    let mut merged: Vec<Password> = ours
+        .iter()
+        .filter(|entry| !base.contains(entry) || theirs.contains(entry))
+        .cloned()
+        .collect();
+    for entry in theirs {
+        if !base.contains(entry) && !merged.contains(entry) {
+            merged.push(entry.clone());
+        }
+    }
+    merged
+}
+
+/// What the sidebar shows of the entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    All,
+    Folder(String),
+    Tag(String),
+}
+
+impl Filter {
+    pub fn matches(&self, entry: &Password) -> bool {
+        match self {
+            Self::All => true,
+            Self::Folder(folder) => entry.folder == *folder,
+            Self::Tag(tag) => entry.tags.contains(tag),
+        }
+    }
+
+    /// All, then every folder and every tag in use, sorted.
+    pub fn list(passwords: &[Password]) -> Vec<Filter> {
+        let mut folders: Vec<&str> = passwords
+            .iter()
+            .map(|entry| entry.folder.as_str())
+            .filter(|folder| !folder.is_empty())
+            .collect();
+        folders.sort_unstable();
+        folders.dedup();
+        let mut tags: Vec<&str> = passwords
+            .iter()
+            .flat_map(|entry| entry.tags.iter().map(String::as_str))
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+
+        let mut filters = vec![Self::All];
+        filters.extend(folders.into_iter().map(|f| Self::Folder(f.to_owned())));
+        filters.extend(tags.into_iter().map(|t| Self::Tag(t.to_owned())));
+        filters
+    }
+}
+
+/// Parses comma separated tags, dropping empty and repeated ones.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = vec![];
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_owned());
+        }
+    }
+    tags
+}
+
+/// Parses custom fields written one per line as `name: value`. A name
+/// starting with `!` marks the value as secret. Lines without a colon are
+/// skipped.
+pub fn parse_fields(text: &str) -> Vec<CustomField> {
+    text.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = name.trim();
+            let (name, secret) = match name.strip_prefix('!') {
+                Some(name) => (name.trim(), true),
+                None => (name, false),
+            };
+            if name.is_empty() {
+                return None;
+            }
+            Some(CustomField {
+                name: name.to_owned(),
+                value: value.trim().to_owned(),
+                secret,
+            })
+        })
+        .collect()
+}
+
+/// Writes custom fields in the form read by [`parse_fields`].
+pub fn format_fields(fields: &[CustomField]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let mark = if field.secret { "!" } else { "" };
+            format!("{}{}: {}", mark, field.name, field.value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_fields_round_trip() {
+        let fields = parse_fields("PIN code: 1234\n!recovery key: a:b:c\nnot a field\n: empty");
+        assert_eq!(
+            fields,
+            vec![
+                CustomField {
+                    name: "PIN code".to_owned(),
+                    value: "1234".to_owned(),
+                    secret: false,
+                },
+                CustomField {
+                    name: "recovery key".to_owned(),
+                    value: "a:b:c".to_owned(),
+                    secret: true,
+                },
+            ]
+        );
+        assert_eq!(parse_fields(&format_fields(&fields)), fields);
+    }
+
+    #[test]
+    fn filters_by_folder_and_tag() {
+        let mut mail = Password::new("mail".to_owned(), "me".to_owned(), "pw".to_owned());
+        mail.folder = "Personal".to_owned();
+        mail.tags = parse_tags("email, 2fa, email,");
+        assert_eq!(mail.tags, vec!["email", "2fa"]);
+        let mut vpn = Password::new("vpn".to_owned(), "me".to_owned(), "pw".to_owned());
+        vpn.folder = "Work".to_owned();
+        vpn.tags = vec!["2fa".to_owned()];
+        let passwords = vec![mail, vpn];
+
+        assert_eq!(
+            Filter::list(&passwords),
+            vec![
+                Filter::All,
+                Filter::Folder("Personal".to_owned()),
+                Filter::Folder("Work".to_owned()),
+                Filter::Tag("2fa".to_owned()),
+                Filter::Tag("email".to_owned()),
+            ]
+        );
+        let shown = |filter: Filter| passwords.iter().filter(|e| filter.matches(e)).count();
+        assert_eq!(shown(Filter::Tag("2fa".to_owned())), 2);
+        assert_eq!(shown(Filter::Folder("Work".to_owned())), 1);
+    }
+
+    #[test]
+    fn replacing_keeps_the_old_passwords() {
+        let mut entry = Password::new("mail".to_owned(), "me".to_owned(), "one".to_owned());
+        for password in ["two", "two", "three", "four"] {
+            let mut new = Password::new("mail".to_owned(), "me".to_owned(), password.to_owned());
+            new.replace(&entry, 2);
+            entry = new;
+        }
+        let history: Vec<&str> = entry
+            .history
+            .iter()
+            .map(|old| old.password.as_str())
+            .collect();
+        assert_eq!(history, vec!["three", "two"]);
+        assert!(entry.history[0].replaced_at <= entry.changed_at);
+
+        let mut new = Password::new("mail".to_owned(), "me".to_owned(), "five".to_owned());
+        new.replace(&entry, 0);
+        assert!(new.history.is_empty());
+    }
+
+    #[test]
+    fn merging_keeps_the_changes_of_both_sides() {
+        let entry = |title: &str, password: &str| {
+            Password::new(title.to_owned(), "me".to_owned(), password.to_owned())
+        };
+        let (mail, bank, work) = (entry("mail", "a"), entry("bank", "b"), entry("work", "c"));
+        let base = vec![mail.clone(), bank.clone(), work.clone()];
+        // 우리는 mail을 고치고 bank를 지웠고, 다른 쪽은 work를 지우고 vpn을 더했다.
+        let mut mail_ours = entry("mail", "a2");
+        mail_ours.replace(&mail, 10);
+        let ours = vec![mail_ours.clone(), work.clone()];
+        let vpn = entry("vpn", "d");
+        let theirs = vec![mail.clone(), bank.clone(), vpn.clone()];
+
+        let merged = merge(&base, &ours, &theirs);
+        let titles: Vec<&str> = merged.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["mail", "vpn"]);
+        assert!(merged[0] == mail_ours);
+
+        // 같은 항목을 양쪽에서 고치면 둘 다 남긴다.
+        let mut mail_theirs = entry("mail", "a3");
+        mail_theirs.replace(&mail, 10);
+        let merged = merge(&base, &ours, &[mail_theirs, bank, work]);
+        let titles: Vec<&str> = merged.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["mail", "work", "mail"]);
+    }
+
+    #[test]
+    fn old_entries_get_defaults() {
+        let entry: Password =
+            serde_json::from_str(r#"{"title": "mail", "username": "me", "password": "pw"}"#)
+                .unwrap();
+        assert!(entry.url.is_empty());
+        assert!(entry.totp.is_empty());
+        assert!(entry.custom.is_empty());
+        assert!(entry.history.is_empty());
+        assert!(!entry.changed_known());
+        assert_eq!(entry.created_at, unknown_date());
+    }
+}
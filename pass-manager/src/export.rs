@@ -0,0 +1,96 @@
+//! Exports the vault to plain CSV or to an encrypted copy.
+
+use std::path::Path;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::entry::{self, Password};
+use crate::vault::{self, Vault, VaultError};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ExportError {
+    #[error("cannot write the file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("cannot write CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("{0}")]
+    Vault(#[from] VaultError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Plain text, readable by the generic CSV import of most managers
+    Csv,
+    /// JSON sealed like the vault, with the master password
+    EncryptedJson,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV (not encrypted)",
+            Self::EncryptedJson => "Encrypted JSON",
+        }
+    }
+}
+
+pub fn to_csv(passwords: &[Password]) -> Result<Zeroizing<Vec<u8>>, ExportError> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record([
+        "title", "username", "password", "totp", "url", "notes", "fields", "folder", "tags",
+    ])?;
+    for entry in passwords {
+        writer.write_record([
+            entry.title.as_str(),
+            &entry.username,
+            &entry.password,
+            &entry.totp,
+            &entry.url,
+            &entry.notes,
+            &entry::format_fields(&entry.custom),
+            &entry.folder,
+            &entry.tags.join(", "),
+        ])?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(Zeroizing::new(bytes))
+}
+
+/// Writes `passwords` to `path`. The encrypted copy is a vault file of its
+/// own, opened with the same master password.
+pub fn export(
+    passwords: &[Password],
+    path: &Path,
+    format: Format,
+    master: &str,
+) -> Result<(), ExportError> {
+    match format {
+        Format::Csv => vault::write_private(path, &to_csv(passwords)?)?,
+        Format::EncryptedJson => Vault::create(path, master)?.save(&passwords)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import;
+
+    #[test]
+    fn csv_export_imports_back() {
+        let mut entry = Password::new("mail".to_owned(), "alice".to_owned(), "a,\"b\"".to_owned());
+        entry.notes = "two\nlines".to_owned();
+        entry.custom = entry::parse_fields("!PIN: 1234");
+        entry.tags = entry::parse_tags("email, 2fa");
+
+        let csv = to_csv(&[entry]).unwrap();
+        let imported = import::read(csv.as_slice(), None, &[]).unwrap();
+        assert_eq!(imported.format, import::Format::Generic);
+        let entry = &imported.passwords[0];
+        assert_eq!(entry.password, "a,\"b\"");
+        assert_eq!(entry.notes, "two\nlines");
+        assert!(entry.custom[0].secret);
+        assert_eq!(entry.tags, vec!["email", "2fa"]);
+    }
+}
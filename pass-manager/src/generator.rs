@@ -0,0 +1,271 @@
+//! Random passwords and diceware passphrases.
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+
+// EFF의 큰 단어 목록(CC BY 3.0). 한 줄에 "주사위 번호\t단어" 형식이다.
+const WORDLIST: &str = include_str!("eff_large_wordlist.txt");
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+// 글꼴에 따라 헷갈리기 쉬운 문자
+const AMBIGUOUS: &str = "Il1|O0o`'\"";
+
+pub const MIN_LENGTH: usize = 8;
+pub const MAX_LENGTH: usize = 128;
+pub const MIN_WORDS: usize = 3;
+pub const MAX_WORDS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Password,
+    Passphrase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Lowercase,
+    Uppercase,
+    Digits,
+    Symbols,
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub style: Style,
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,
+    pub words: usize,
+    pub separator: char,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            style: Style::Password,
+            length: 20,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+            words: 6,
+            separator: '-',
+        }
+    }
+}
+
+impl Options {
+    /// Turns a character class on or off. The last enabled class stays on.
+    pub fn toggle(&mut self, class: Class) {
+        let enabled = [self.lowercase, self.uppercase, self.digits, self.symbols]
+            .iter()
+            .filter(|on| **on)
+            .count();
+        let flag = match class {
+            Class::Lowercase => &mut self.lowercase,
+            Class::Uppercase => &mut self.uppercase,
+            Class::Digits => &mut self.digits,
+            Class::Symbols => &mut self.symbols,
+        };
+        if !*flag || enabled > 1 {
+            *flag = !*flag;
+        }
+    }
+
+    /// Grows or shrinks the length or the word count, within bounds.
+    pub fn resize(&mut self, grow: bool) {
+        let (value, min, max) = match self.style {
+            Style::Password => (&mut self.length, MIN_LENGTH, MAX_LENGTH),
+            Style::Passphrase => (&mut self.words, MIN_WORDS, MAX_WORDS),
+        };
+        *value = match grow {
+            true => (*value + 1).min(max),
+            false => value.saturating_sub(1).max(min),
+        };
+    }
+
+    fn classes(&self) -> Vec<Vec<char>> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, chars)| {
+            chars
+                .chars()
+                .filter(|c| !self.exclude_ambiguous || !AMBIGUOUS.contains(*c))
+                .collect()
+        })
+        .collect()
+    }
+
+    pub fn generate(&self) -> String {
+        match self.style {
+            Style::Password => self.generate_password(),
+            Style::Passphrase => {
+                let words = words();
+                let separator = self.separator.to_string();
+                (0..self.words)
+                    .map(|_| words[random_below(words.len())])
+                    .collect::<Vec<_>>()
+                    .join(&separator)
+            }
+        }
+    }
+
+    // 켜 둔 문자 종류가 하나씩은 들어가도록 먼저 뽑고 나머지를 채운 뒤 섞는다.
+    fn generate_password(&self) -> String {
+        let classes = self.classes();
+        let pool: Vec<char> = classes.concat();
+
+        let mut chars: Vec<char> = classes
+            .iter()
+            .take(self.length)
+            .map(|class| class[random_below(class.len())])
+            .collect();
+        while chars.len() < self.length {
+            chars.push(pool[random_below(pool.len())]);
+        }
+        for i in (1..chars.len()).rev() {
+            chars.swap(i, random_below(i + 1));
+        }
+        chars.into_iter().collect()
+    }
+
+    /// Bits of entropy of a value generated with these options.
+    pub fn entropy(&self) -> f64 {
+        match self.style {
+            Style::Password => {
+                let pool = self.classes().iter().map(Vec::len).sum::<usize>();
+                self.length as f64 * (pool as f64).log2()
+            }
+            Style::Passphrase => self.words as f64 * (words().len() as f64).log2(),
+        }
+    }
+}
+
+/// A rough rating of `bits` of entropy.
+pub fn strength(bits: f64) -> &'static str {
+    match bits {
+        b if b < 40.0 => "weak",
+        b if b < 60.0 => "fair",
+        b if b < 80.0 => "strong",
+        _ => "very strong",
+    }
+}
+
+fn words() -> Vec<&'static str> {
+    WORDLIST
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .collect()
+}
+
+// 나머지 연산의 편향을 피하려고 범위를 벗어난 값은 버리고 다시 뽑는다.
+fn random_below(bound: usize) -> usize {
+    let bound = bound as u64;
+    let zone = u64::MAX - u64::MAX % bound;
+    loop {
+        let value = OsRng.next_u64();
+        if value < zone {
+            return (value % bound) as usize;
+        }
+    }
+}
+
+/// The generator dialog: its options and the value on show.
+pub struct Generator {
+    pub options: Options,
+    pub value: String,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        let options = Options::default();
+        let value = options.generate();
+        Self { options, value }
+    }
+
+    pub fn regenerate(&mut self) {
+        self.value = self.options.generate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passwords_follow_the_options() {
+        let options = Options {
+            length: 40,
+            symbols: false,
+            exclude_ambiguous: true,
+            ..Options::default()
+        };
+        for _ in 0..20 {
+            let password = options.generate();
+            assert_eq!(password.chars().count(), 40);
+            assert!(password.chars().all(char::is_alphanumeric));
+            assert!(!password.contains(|c| AMBIGUOUS.contains(c)));
+            assert!(password.contains(|c: char| c.is_ascii_lowercase()));
+            assert!(password.contains(|c: char| c.is_ascii_uppercase()));
+            assert!(password.contains(|c: char| c.is_ascii_digit()));
+        }
+    }
+
+    #[test]
+    fn the_last_class_cannot_be_disabled() {
+        let mut options = Options::default();
+        options.toggle(Class::Uppercase);
+        options.toggle(Class::Digits);
+        options.toggle(Class::Symbols);
+        options.toggle(Class::Lowercase);
+        assert!(options.lowercase);
+        assert!(options.generate().chars().all(|c| c.is_ascii_lowercase()));
+    }
+
+    #[test]
+    fn passphrases_use_the_wordlist() {
+        assert_eq!(words().len(), 7776);
+        let options = Options {
+            style: Style::Passphrase,
+            words: 5,
+            // 단어 중에 t-shirt처럼 하이픈이 들어간 것이 있다.
+            separator: ' ',
+            ..Options::default()
+        };
+        let passphrase = options.generate();
+        assert_eq!(passphrase.split(' ').count(), 5);
+        assert!(passphrase.split(' ').all(|word| words().contains(&word)));
+    }
+
+    #[test]
+    fn entropy() {
+        let options = Options {
+            length: 10,
+            uppercase: false,
+            symbols: false,
+            ..Options::default()
+        };
+        assert!((options.entropy() - 10.0 * 36f64.log2()).abs() < 1e-9);
+
+        let options = Options {
+            style: Style::Passphrase,
+            words: 6,
+            ..Options::default()
+        };
+        assert!((options.entropy() - 6.0 * 7776f64.log2()).abs() < 1e-9);
+        assert_eq!(strength(options.entropy()), "strong");
+    }
+}
@@ -0,0 +1,401 @@
+//! Imports CSV exports of other password managers.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::entry::{self, Password};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ImportError {
+    #[error("cannot read the file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("no {0} column, map one with --map {0}=<column>")]
+    Missing(Column),
+    #[error("no column named {0:?}")]
+    UnknownColumn(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Bitwarden,
+    OnePassword,
+    KeePass,
+    Chrome,
+    /// Any CSV with recognizable column names
+    Generic,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] = [
+        Self::Bitwarden,
+        Self::OnePassword,
+        Self::KeePass,
+        Self::Chrome,
+        Self::Generic,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bitwarden => "bitwarden",
+            Self::OnePassword => "1password",
+            Self::KeePass => "keepass",
+            Self::Chrome => "chrome",
+            Self::Generic => "generic",
+        }
+    }
+
+    // 각 프로그램이 내보내는 CSV의 열 이름
+    fn header(self, column: Column) -> &'static [&'static str] {
+        use Column::*;
+        match (self, column) {
+            (Self::Bitwarden, Title) => &["name"],
+            (Self::Bitwarden, Username) => &["login_username"],
+            (Self::Bitwarden, Password) => &["login_password"],
+            (Self::Bitwarden, Totp) => &["login_totp"],
+            (Self::Bitwarden, Url) => &["login_uri"],
+            (Self::Bitwarden, Notes) => &["notes"],
+            (Self::Bitwarden, Fields) => &["fields"],
+            (Self::Bitwarden, Folder) => &["folder"],
+            (Self::OnePassword, Title) => &["title"],
+            (Self::OnePassword, Username) => &["username"],
+            (Self::OnePassword, Password) => &["password"],
+            (Self::OnePassword, Totp) => &["otpauth"],
+            (Self::OnePassword, Url) => &["url"],
+            (Self::OnePassword, Notes) => &["notes"],
+            (Self::OnePassword, Tags) => &["tags"],
+            (Self::KeePass, Title) => &["title"],
+            (Self::KeePass, Username) => &["username", "user name"],
+            (Self::KeePass, Password) => &["password"],
+            (Self::KeePass, Totp) => &["totp"],
+            (Self::KeePass, Url) => &["url"],
+            (Self::KeePass, Notes) => &["notes"],
+            (Self::KeePass, Folder) => &["group"],
+            (Self::Chrome, Title) => &["name"],
+            (Self::Chrome, Username) => &["username"],
+            (Self::Chrome, Password) => &["password"],
+            (Self::Chrome, Url) => &["url"],
+            (Self::Chrome, Notes) => &["note"],
+            (Self::Generic, Title) => &["title", "name", "account"],
+            (Self::Generic, Username) => &["username", "user", "login", "email"],
+            (Self::Generic, Password) => &["password", "pass"],
+            (Self::Generic, Totp) => &["totp", "otp", "otpauth"],
+            (Self::Generic, Url) => &["url", "uri", "website", "login_uri"],
+            (Self::Generic, Notes) => &["notes", "note", "comments"],
+            (Self::Generic, Fields) => &["fields"],
+            (Self::Generic, Folder) => &["folder", "group"],
+            (Self::Generic, Tags) => &["tags"],
+            _ => &[],
+        }
+    }
+
+    // 그 프로그램의 내보내기에만 있는 열
+    fn signature(self) -> &'static [&'static str] {
+        match self {
+            Self::Bitwarden => &["login_password", "reprompt"],
+            Self::OnePassword => &["title", "otpauth", "archived"],
+            Self::KeePass => &["group", "title", "last modified"],
+            Self::Chrome => &["name", "url", "username", "password", "note"],
+            Self::Generic => &[],
+        }
+    }
+
+    /// Guesses the format from the header row.
+    pub fn detect(headers: &[String]) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|format| {
+                format
+                    .signature()
+                    .iter()
+                    .all(|name| headers.iter().any(|header| header == name))
+            })
+            .unwrap_or(Self::Generic)
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.name() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown format {:?}", s))
+    }
+}
+
+/// A field of an entry that a CSV column can fill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Title,
+    Username,
+    Password,
+    Totp,
+    Url,
+    Notes,
+    Fields,
+    Folder,
+    Tags,
+}
+
+impl Column {
+    const ALL: [Column; 9] = [
+        Self::Title,
+        Self::Username,
+        Self::Password,
+        Self::Totp,
+        Self::Url,
+        Self::Notes,
+        Self::Fields,
+        Self::Folder,
+        Self::Tags,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Username => "username",
+            Self::Password => "password",
+            Self::Totp => "totp",
+            Self::Url => "url",
+            Self::Notes => "notes",
+            Self::Fields => "fields",
+            Self::Folder => "folder",
+            Self::Tags => "tags",
+        }
+    }
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|column| column.name() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown field {:?}", s))
+    }
+}
+
+/// A `field=column` pair given on the command line, overriding the column
+/// the format would use.
+#[derive(Debug, Clone)]
+pub struct MapArg(pub Column, pub String);
+
+impl FromStr for MapArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, column) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected field=column, got {:?}", s))?;
+        Ok(Self(field.trim().parse()?, column.trim().to_owned()))
+    }
+}
+
+/// The rows of an export, read into entries.
+pub struct Imported {
+    pub format: Format,
+    pub passwords: Vec<Password>,
+}
+
+pub fn read_file(
+    path: &Path,
+    format: Option<Format>,
+    map: &[MapArg],
+) -> Result<Imported, ImportError> {
+    read(File::open(path)?, format, map)
+}
+
+/// Reads CSV rows into entries, detecting the format from the header row
+/// unless it's given. Rows without a title are skipped.
+pub fn read<R: Read>(
+    reader: R,
+    format: Option<Format>,
+    map: &[MapArg],
+) -> Result<Imported, ImportError> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|header| header.trim().to_lowercase())
+        .collect();
+    let format = format.unwrap_or_else(|| Format::detect(&headers));
+
+    let mut columns: HashMap<Column, usize> = Column::ALL
+        .into_iter()
+        .filter_map(|column| {
+            let index = format
+                .header(column)
+                .iter()
+                .find_map(|name| headers.iter().position(|header| header == name))?;
+            Some((column, index))
+        })
+        .collect();
+    for MapArg(column, name) in map {
+        let index = headers
+            .iter()
+            .position(|header| *header == name.to_lowercase())
+            .ok_or_else(|| ImportError::UnknownColumn(name.to_owned()))?;
+        columns.insert(*column, index);
+    }
+    for column in [Column::Title, Column::Password] {
+        if !columns.contains_key(&column) {
+            return Err(ImportError::Missing(column));
+        }
+    }
+
+    let mut passwords = vec![];
+    for record in reader.records() {
+        let record = record?;
+        let get = |column| {
+            columns
+                .get(&column)
+                .and_then(|index| record.get(*index))
+                .unwrap_or_default()
+                .to_owned()
+        };
+        // 비밀번호와 메모는 공백도 값의 일부라 다듬지 않는다.
+        let title = get(Column::Title).trim().to_owned();
+        if title.is_empty() {
+            continue;
+        }
+        let username = get(Column::Username).trim().to_owned();
+        let mut password = Password::new(title, username, get(Column::Password));
+        password.totp = get(Column::Totp).trim().to_owned();
+        password.url = get(Column::Url).trim().to_owned();
+        password.notes = get(Column::Notes);
+        password.custom = entry::parse_fields(&get(Column::Fields));
+        password.folder = get(Column::Folder).trim().to_owned();
+        password.tags = entry::parse_tags(&get(Column::Tags));
+        passwords.push(password);
+    }
+    Ok(Imported { format, passwords })
+}
+
+/// What [`merge`] did.
+#[derive(Debug, Default)]
+pub struct Merged {
+    pub added: usize,
+    /// Titles of the entries skipped as duplicates
+    pub duplicates: Vec<String>,
+}
+
+impl Merged {
+    pub fn summary(&self) -> String {
+        match self.duplicates.len() {
+            0 => format!("Imported {} entries", self.added),
+            n => format!(
+                "Imported {} entries, skipped {} duplicates: {}",
+                self.added,
+                n,
+                self.duplicates.join(", ")
+            ),
+        }
+    }
+}
+
+/// Adds the imported entries to `passwords`, skipping the ones with the
+/// same title, username and password as an entry already there.
+pub fn merge(passwords: &mut Vec<Password>, imported: Vec<Password>) -> Merged {
+    let mut merged = Merged::default();
+    for entry in imported {
+        let duplicate = passwords.iter().any(|existing| {
+            existing.title.to_lowercase() == entry.title.to_lowercase()
+                && existing.username == entry.username
+                && existing.password == entry.password
+        });
+        if duplicate {
+            merged.duplicates.push(entry.title.to_owned());
+        } else {
+            passwords.push(entry);
+            merged.added += 1;
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_format_and_maps_columns() {
+        let bitwarden = "folder,favorite,type,name,notes,fields,reprompt,login_uri,login_username,login_password,login_totp\n\
+            Personal,,login,mail,my notes,\"PIN: 1234\",0,https://mail.example,alice,s3cret,JBSWY3DPEHPK3PXP\n\
+            ,,note,,only a note,,0,,,,\n";
+        let imported = read(bitwarden.as_bytes(), None, &[]).unwrap();
+        assert_eq!(imported.format, Format::Bitwarden);
+        assert_eq!(imported.passwords.len(), 1);
+        let entry = &imported.passwords[0];
+        assert_eq!(
+            (entry.username.as_str(), entry.password.as_str()),
+            ("alice", "s3cret")
+        );
+        assert_eq!(entry.url, "https://mail.example");
+        assert_eq!(entry.totp, "JBSWY3DPEHPK3PXP");
+        assert_eq!(entry.custom[0].value, "1234");
+        assert_eq!(entry.folder, "Personal");
+
+        let chrome = "name,url,username,password,note\nbank,https://bank.example,bob,pw,\n";
+        let imported = read(chrome.as_bytes(), None, &[]).unwrap();
+        assert_eq!(imported.format, Format::Chrome);
+        assert_eq!(imported.passwords[0].title, "bank");
+
+        let keepass = "\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\",\"TOTP\",\"Icon\",\"Last Modified\",\"Created\"\n\
+            \"Root\",\"forum\",\"carol\",\"hunter2\",\"\",\"\",\"\",\"0\",\"\",\"\"\n";
+        let imported = read(keepass.as_bytes(), None, &[]).unwrap();
+        assert_eq!(imported.format, Format::KeePass);
+        assert_eq!(imported.passwords[0].password, "hunter2");
+    }
+
+    #[test]
+    fn columns_can_be_mapped_by_hand() {
+        let csv = "Site,Login,Secret\nmail,alice,pw\n";
+        assert!(matches!(
+            read(csv.as_bytes(), None, &[]),
+            Err(ImportError::Missing(Column::Title))
+        ));
+
+        let map: Vec<MapArg> = ["title=Site", "username=login", "password=Secret"]
+            .iter()
+            .map(|arg| arg.parse().unwrap())
+            .collect();
+        let imported = read(csv.as_bytes(), None, &map).unwrap();
+        assert_eq!(imported.passwords[0].title, "mail");
+        assert_eq!(imported.passwords[0].username, "alice");
+        assert_eq!(imported.passwords[0].password, "pw");
+    }
+
+    #[test]
+    fn merge_skips_duplicates() {
+        let entry = |title: &str, password: &str| {
+            Password::new(title.to_owned(), "me".to_owned(), password.to_owned())
+        };
+        let mut passwords = vec![entry("Mail", "pw")];
+        let merged = merge(
+            &mut passwords,
+            vec![
+                entry("mail", "pw"),
+                entry("mail", "new"),
+                entry("bank", "pw"),
+            ],
+        );
+        assert_eq!(merged.added, 2);
+        assert_eq!(merged.duplicates, vec!["mail"]);
+        assert_eq!(passwords.len(), 3);
+    }
+}
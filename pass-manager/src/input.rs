@@ -0,0 +1,110 @@
+//! Editing of the text fields at a cursor, which Left/Right and Home/End
+//! move. The cursor counts characters, not bytes.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn byte_index(text: &str, cursor: usize) -> usize {
+    text.char_indices()
+        .nth(cursor)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+/// Applies `key` to `text`. Enter starts a new line in a multi-line field.
+/// Returns false for the keys that don't edit text.
+pub fn edit(text: &mut String, cursor: &mut usize, key: &KeyEvent, multiline: bool) -> bool {
+    let len = text.chars().count();
+    *cursor = (*cursor).min(len);
+    // 현재 줄의 시작과 끝
+    let line_start = text[..byte_index(text, *cursor)]
+        .rfind('\n')
+        .map_or(0, |index| text[..=index].chars().count());
+    let line_end = *cursor
+        + text[byte_index(text, *cursor)..]
+            .chars()
+            .take_while(|c| *c != '\n')
+            .count();
+
+    match key.code {
+        KeyCode::Char(c)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            text.insert(byte_index(text, *cursor), c);
+            *cursor += 1;
+        }
+        KeyCode::Enter if multiline => {
+            text.insert(byte_index(text, *cursor), '\n');
+            *cursor += 1;
+        }
+        KeyCode::Backspace if *cursor > 0 => {
+            *cursor -= 1;
+            text.remove(byte_index(text, *cursor));
+        }
+        KeyCode::Delete if *cursor < len => {
+            text.remove(byte_index(text, *cursor));
+        }
+        KeyCode::Left => *cursor = cursor.saturating_sub(1),
+        KeyCode::Right => *cursor = (*cursor + 1).min(len),
+        KeyCode::Home => *cursor = line_start,
+        KeyCode::End => *cursor = line_end,
+        KeyCode::Backspace | KeyCode::Delete => {}
+        _ => return false,
+    }
+    true
+}
+
+/// The text before the cursor.
+pub fn before(text: &str, cursor: usize) -> &str {
+    &text[..byte_index(text, cursor)]
+}
+
+/// Column and row of the cursor in `text`, counted from 0.
+pub fn position(text: &str, cursor: usize) -> (u16, u16) {
+    let before = before(text, cursor);
+    let row = before.matches('\n').count();
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count();
+    (column as u16, row as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(text: &mut String, cursor: &mut usize, code: KeyCode) {
+        edit(text, cursor, &KeyEvent::new(code, KeyModifiers::NONE), true);
+    }
+
+    #[test]
+    fn edits_at_the_cursor() {
+        let mut text = "héllo".to_owned();
+        let mut cursor = 5;
+        press(&mut text, &mut cursor, KeyCode::Left);
+        press(&mut text, &mut cursor, KeyCode::Left);
+        press(&mut text, &mut cursor, KeyCode::Backspace);
+        assert_eq!((text.as_str(), cursor), ("hélo", 2));
+        press(&mut text, &mut cursor, KeyCode::Char('X'));
+        press(&mut text, &mut cursor, KeyCode::Home);
+        press(&mut text, &mut cursor, KeyCode::Delete);
+        assert_eq!((text.as_str(), cursor), ("éXlo", 0));
+
+        press(&mut text, &mut cursor, KeyCode::End);
+        press(&mut text, &mut cursor, KeyCode::Enter);
+        press(&mut text, &mut cursor, KeyCode::Char('a'));
+        press(&mut text, &mut cursor, KeyCode::Home);
+        assert_eq!(position(&text, cursor), (0, 1));
+        press(&mut text, &mut cursor, KeyCode::End);
+        assert_eq!(
+            (text.as_str(), position(&text, cursor)),
+            ("éXlo\na", (1, 1))
+        );
+
+        let ctrl_a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL);
+        assert!(!edit(&mut text, &mut cursor, &ctrl_a, false));
+    }
+}
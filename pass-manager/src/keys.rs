@@ -0,0 +1,406 @@
+//! Key bindings of the TUI. Every action has default keys, which the `[keys]`
+//! table of the config file can replace:
+//!
+//! ```toml
+//! [keys]
+//! new_entry = ["insert", "n"]
+//! copy_password = "ctrl+p"
+//! ```
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum KeyError {
+    #[error("unknown action {0:?}")]
+    UnknownAction(String),
+    #[error("invalid key {0:?}")]
+    InvalidKey(String),
+    #[error("{0} is bound to both {1} and {2}")]
+    Conflict(Key, &'static str, &'static str),
+}
+
+/// What a key does. Keys that edit text, Tab between fields, Esc and the
+/// arrows moving the selection are not bindable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    ToggleList,
+    NewEntry,
+    Search,
+    Audit,
+    Filter,
+    Import,
+    Export,
+    Details,
+    CopyUsername,
+    CopyPassword,
+    CopyTotp,
+    Reveal,
+    Edit,
+    Delete,
+    Move,
+    Tags,
+    History,
+    Generate,
+    ShowPassword,
+    SearchCopyUsername,
+    SearchCopyPassword,
+}
+
+impl Action {
+    pub const ALL: [Action; 23] = [
+        Self::Quit,
+        Self::Help,
+        Self::ToggleList,
+        Self::NewEntry,
+        Self::Search,
+        Self::Audit,
+        Self::Filter,
+        Self::Import,
+        Self::Export,
+        Self::Details,
+        Self::CopyUsername,
+        Self::CopyPassword,
+        Self::CopyTotp,
+        Self::Reveal,
+        Self::Edit,
+        Self::Delete,
+        Self::Move,
+        Self::Tags,
+        Self::History,
+        Self::Generate,
+        Self::ShowPassword,
+        Self::SearchCopyUsername,
+        Self::SearchCopyPassword,
+    ];
+
+    /// The name in the `[keys]` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Help => "help",
+            Self::ToggleList => "toggle_list",
+            Self::NewEntry => "new_entry",
+            Self::Search => "search",
+            Self::Audit => "audit",
+            Self::Filter => "filter",
+            Self::Import => "import",
+            Self::Export => "export",
+            Self::Details => "details",
+            Self::CopyUsername => "copy_username",
+            Self::CopyPassword => "copy_password",
+            Self::CopyTotp => "copy_totp",
+            Self::Reveal => "reveal",
+            Self::Edit => "edit",
+            Self::Delete => "delete",
+            Self::Move => "move",
+            Self::Tags => "tags",
+            Self::History => "history",
+            Self::Generate => "generate",
+            Self::ShowPassword => "show_password",
+            Self::SearchCopyUsername => "search_copy_username",
+            Self::SearchCopyPassword => "search_copy_password",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Quit => "Quit",
+            Self::Help => "Show the key bindings",
+            Self::ToggleList => "Show/hide the list",
+            Self::NewEntry => "Add an entry",
+            Self::Search => "Search, then Up/Down and Enter to edit",
+            Self::Audit => "Audit the passwords",
+            Self::Filter => "Filter by folder or tag, Up/Down to pick",
+            Self::Import => "Import a CSV export of another password manager",
+            Self::Export => "Export the passwords",
+            Self::Details => "On the list, show the details",
+            Self::CopyUsername => "On the list, copy the username",
+            Self::CopyPassword => "On the list, copy the password",
+            Self::CopyTotp => "On the list, copy the 2FA code",
+            Self::Reveal => "On the list, show the password for a moment",
+            Self::Edit => "On the list, edit the entry",
+            Self::Delete => "On the list, delete the entry",
+            Self::Move => "On the list, move the entry to a folder",
+            Self::Tags => "On the list, edit the tags",
+            Self::History => "In the details, select a previous password",
+            Self::Generate => "In the password field, generate one",
+            Self::ShowPassword => "In the password field, show/hide it",
+            Self::SearchCopyUsername => "In the search, copy the username",
+            Self::SearchCopyPassword => "In the search, copy the password",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Self::Quit => &["q"],
+            Self::Help => &["?"],
+            Self::ToggleList => &["l"],
+            // Insert 키가 없는 노트북도 있다.
+            Self::NewEntry => &["insert", "n"],
+            Self::Search => &["s"],
+            Self::Audit => &["a"],
+            Self::Filter => &["f"],
+            Self::Import => &["i"],
+            Self::Export => &["x"],
+            Self::Details => &["enter"],
+            Self::CopyUsername => &["u"],
+            Self::CopyPassword => &["p"],
+            Self::CopyTotp => &["t"],
+            Self::Reveal => &["v"],
+            Self::Edit => &["e"],
+            Self::Delete => &["d"],
+            Self::Move => &["m"],
+            Self::Tags => &["#"],
+            Self::History => &["tab"],
+            Self::Generate => &["ctrl+g"],
+            Self::ShowPassword => &["ctrl+r"],
+            Self::SearchCopyUsername => &["ctrl+u"],
+            Self::SearchCopyPassword => &["ctrl+p"],
+        }
+    }
+}
+
+/// A key with its modifiers, written like `ctrl+g`, `shift+tab` or `#`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        // 문자와 Shift+Tab은 Shift가 이미 키에 들어 있다.
+        let modifiers = match event.code {
+            KeyCode::Char(_) | KeyCode::BackTab => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for Key {
+    type Err = KeyError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || KeyError::InvalidKey(text.to_owned());
+        // "+" 자체도 키가 될 수 있어서 마지막 부분은 따로 나눈다.
+        let (prefix, name) = match text.len() > 1 && text.ends_with('+') {
+            true => (&text[..text.len() - 1], "+"),
+            false => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        if let KeyCode::Char(_) | KeyCode::BackTab = code {
+            modifiers -= KeyModifiers::SHIFT;
+        }
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if !self.modifiers.is_empty() => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::PageUp => f.write_str("PageUp"),
+            KeyCode::PageDown => f.write_str("PageDown"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// One key or a list of keys in the config file. An empty list unbinds the
+/// action.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    keys: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self::new(&HashMap::new()).expect("the default bindings are valid")
+    }
+}
+
+impl Bindings {
+    /// The default bindings, with the actions in `overrides` bound to their
+    /// keys instead. A key can't do two things.
+    pub fn new(overrides: &HashMap<String, KeyList>) -> Result<Self, KeyError> {
+        if let Some(name) = overrides
+            .keys()
+            .find(|name| !Action::ALL.iter().any(|action| action.name() == *name))
+        {
+            return Err(KeyError::UnknownAction(name.to_owned()));
+        }
+
+        let mut keys = vec![];
+        for action in Action::ALL {
+            let names: Vec<&str> = match overrides.get(action.name()) {
+                Some(KeyList::One(name)) => vec![name],
+                Some(KeyList::Many(names)) => names.iter().map(String::as_str).collect(),
+                None => action.default_keys().to_vec(),
+            };
+            let parsed = names
+                .into_iter()
+                .map(Key::from_str)
+                .collect::<Result<Vec<_>, _>>()?;
+            keys.push((action, parsed));
+        }
+
+        for (i, (action, bound)) in keys.iter().enumerate() {
+            for (other, other_bound) in &keys[i + 1..] {
+                if let Some(key) = bound.iter().find(|key| other_bound.contains(key)) {
+                    return Err(KeyError::Conflict(*key, action.name(), other.name()));
+                }
+            }
+        }
+        Ok(Self { keys })
+    }
+
+    /// The action bound to the pressed key.
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|key| key.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// The keys of `action` for display, like `Insert/n`.
+    pub fn keys(&self, action: Action) -> String {
+        let keys = self
+            .keys
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default();
+        match keys {
+            [] => "(unbound)".to_owned(),
+            keys => keys
+                .iter()
+                .map(Key::to_string)
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+
+    /// Every action with its keys, for the help overlay.
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        Action::ALL
+            .iter()
+            .map(|action| (self.keys(*action), action.description()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_matches_keys() {
+        let key: Key = "ctrl+G".parse().unwrap();
+        assert!(key.matches(&KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL)));
+        assert_eq!(key.to_string(), "Ctrl+G");
+
+        // 터미널은 ?와 #를 Shift와 함께 보낸다.
+        let key: Key = "?".parse().unwrap();
+        assert!(key.matches(&KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+        assert_eq!("shift+tab".parse::<Key>().unwrap().to_string(), "Shift+Tab");
+        assert_eq!("insert".parse::<Key>().unwrap().code, KeyCode::Insert);
+        assert_eq!("ctrl++".parse::<Key>().unwrap().code, KeyCode::Char('+'));
+        assert!("hyper+x".parse::<Key>().is_err());
+        assert!("f13".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn overrides_replace_the_defaults() {
+        let overrides = HashMap::from([
+            ("new_entry".to_owned(), KeyList::One("ctrl+n".to_owned())),
+            ("quit".to_owned(), KeyList::Many(vec![])),
+        ]);
+        let bindings = Bindings::new(&overrides).unwrap();
+        let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(bindings.action(&ctrl_n), Some(Action::NewEntry));
+        let insert = KeyEvent::new(KeyCode::Insert, KeyModifiers::NONE);
+        assert_eq!(bindings.action(&insert), None);
+        assert_eq!(bindings.keys(Action::Quit), "(unbound)");
+
+        let clash = HashMap::from([("search".to_owned(), KeyList::One("q".to_owned()))]);
+        assert!(matches!(
+            Bindings::new(&clash),
+            Err(KeyError::Conflict(_, "quit", "search"))
+        ));
+        let unknown = HashMap::from([("fly".to_owned(), KeyList::One("z".to_owned()))]);
+        assert!(matches!(
+            Bindings::new(&unknown),
+            Err(KeyError::UnknownAction(_))
+        ));
+    }
+}
@@ -1,115 +1,777 @@
+use chrono::{DateTime, Local};
+use clap::Parser;
 use crossterm::event::Event::Key;
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyCode};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::{event, execute};
 use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tui::backend::{Backend, CrosstermBackend};
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
-use tui::text::Span;
-use tui::widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph};
+use tui::text::{Span, Spans};
+use tui::widgets::{
+    Block, BorderType, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row,
+    Table, Wrap,
+};
 use tui::{Frame, Terminal};
+use zeroize::{Zeroize, Zeroizing};
+
+#[cfg(unix)]
+use agent::Client;
+use audit::Report;
+use breach::{BreachList, Breaches};
+use cli::{Cli, Master};
+use clipboard::SecretClipboard;
+use config::Config;
+use entry::{Filter, Password};
+use generator::{Class, Generator};
+use keys::{Action, Bindings};
+use search::{Field, Hit};
+use store::Store;
+use totp::Totp;
+use vault::Vault;
+
+#[cfg(unix)]
+mod agent;
+mod audit;
+mod breach;
+mod cli;
+mod clipboard;
+mod config;
+mod entry;
+mod export;
+mod generator;
+mod import;
+mod input;
+mod keys;
+mod search;
+mod store;
+mod totp;
+mod vault;
 
-const APP_KEYS_DESC: &str = r#"
-L:           Show/Hide List
-U:           On list, It's copy the Username
-P:           On list, It's copy the Password
-D:           On list, It's Delete
-E:           On list, It's Edit
-S:           Search
-Insert:      Insert new Password
-Tab:         Go to next field
-Shift+Tab:   Go to previous filed
-Esc:         Exit insert mode
-"#;
-
-#[derive(Eq, PartialEq)]
+// 클립보드 카운트다운을 갱신하기 위해 입력이 없어도 이 간격으로 다시 그린다.
+const TICK: Duration = Duration::from_millis(250);
+// 목록에서 드러낸 비밀번호를 다시 가리기까지의 시간
+const REVEAL_FOR: Duration = Duration::from_secs(5);
+const MASK: &str = "••••••••";
+
+#[derive(Clone, Copy, Eq, PartialEq)]
 enum InputMode {
+    Unlock,
     Normal,
     Title,
     Username,
     Password,
+    Totp,
+    Url,
+    Notes,
+    Fields,
     Submit,
     Search,
     List,
     Delete,
-}
-
-#[derive(Clone)]
-struct Password {
-    title: String,
-    username: String,
-    password: String,
-}
-impl Password {
-    pub fn new(title: String, username: String, password: String) -> Self {
-        Self {
-            title,
-            username,
-            password,
-        }
-    }
+    Generator,
+    Audit,
+    Detail,
+    Import,
+    Export,
+    ExportConfirm,
+    Sidebar,
+    Move,
+    Tag,
 }
 
 struct PassManager {
     mode: InputMode,
+    vault_path: PathBuf,
+    vault: Option<Store>,
+    #[cfg(unix)]
+    agent_socket: PathBuf,
+    master_txt: String,
+    // 새 금고를 만들 때 처음 입력한 마스터 비밀번호
+    master_first: Option<Zeroizing<String>>,
+    message: String,
+    clipboard: SecretClipboard,
     list_state: ListState,
     passwords: Vec<Password>,
+    // 마지막으로 금고에서 읽거나 쓴 항목. 저장할 때 다른 곳의 변경과 합친다.
+    loaded: Vec<Password>,
+    // 사이드바에서 고른 폴더나 태그
+    filter: Filter,
+    sidebar_state: ListState,
+    // 옮길 폴더 이름이나 쉼표로 구분한 태그
+    organize_txt: String,
     search_txt: String,
-    search_list: Vec<Password>,
+    search_list: Vec<Hit>,
+    search_state: ListState,
     new_title: String,
     new_username: String,
     new_password: String,
+    new_totp: String,
+    new_url: String,
+    new_notes: String,
+    // 한 줄에 "이름: 값" 하나, 이름 앞에 !가 있으면 비밀 값이다.
+    new_fields: String,
+    detail_scroll: u16,
+    // 상세 화면에서 고른 이전 비밀번호
+    history_selected: Option<usize>,
+    history_depth: usize,
+    // 수정 중인 항목의 위치. 없으면 새 항목을 추가한다.
+    editing: Option<usize>,
+    generator: Generator,
+    max_password_age: u32,
+    // 비밀번호를 드러낸 항목과 다시 가릴 시각
+    revealed: Option<(usize, Instant)>,
+    show_new_password: bool,
+    lock_timeout: Option<Duration>,
+    last_activity: Instant,
+    // 가져오거나 내보낼 파일 경로
+    transfer_path: String,
+    export_format: export::Format,
+    // 이미 있는 파일에 덮어써도 된다고 확인했는지
+    export_overwrite: bool,
+    breach_list: Option<PathBuf>,
+    // 유출 목록에서 찾은 횟수, 비밀번호의 SHA-1 기준
+    breaches: Breaches,
+    keys: Bindings,
+    show_help: bool,
+    // 입력 중인 칸에서 커서의 위치 (문자 단위)
+    cursor: usize,
 }
 impl PassManager {
-    fn new() -> Self {
+    fn new(config: Config) -> Self {
+        let clipboard = SecretClipboard::new(config.clipboard_timeout);
         Self {
-            mode: InputMode::Normal,
+            mode: InputMode::Unlock,
+            vault_path: config.vault,
+            vault: None,
+            #[cfg(unix)]
+            agent_socket: config.agent_socket,
+            master_txt: String::new(),
+            master_first: None,
+            message: String::new(),
+            clipboard,
             list_state: ListState::default(),
             passwords: vec![],
+            loaded: vec![],
+            filter: Filter::All,
+            sidebar_state: ListState::default(),
+            organize_txt: String::new(),
             search_txt: String::new(),
             search_list: vec![],
+            search_state: ListState::default(),
             new_title: String::new(),
             new_username: String::new(),
             new_password: String::new(),
+            new_totp: String::new(),
+            new_url: String::new(),
+            new_notes: String::new(),
+            new_fields: String::new(),
+            detail_scroll: 0,
+            history_selected: None,
+            history_depth: config.history_depth,
+            editing: None,
+            generator: Generator::new(),
+            max_password_age: config.max_password_age,
+            revealed: None,
+            show_new_password: false,
+            lock_timeout: config.lock_timeout,
+            last_activity: Instant::now(),
+            transfer_path: String::new(),
+            export_format: export::Format::EncryptedJson,
+            export_overwrite: false,
+            breach_list: config.breach_list,
+            breaches: Breaches::default(),
+            keys: config.keys,
+            show_help: false,
+            cursor: 0,
         }
     }
 
     pub fn change_mode(&mut self, mode: InputMode) {
+        if mode != InputMode::Detail {
+            self.history_selected = None;
+        }
         self.mode = mode;
+        self.cursor = self.field().map_or(0, |text| text.chars().count());
+    }
+
+    /// The text typed in the current mode.
+    fn field(&mut self) -> Option<&mut String> {
+        match self.mode {
+            InputMode::Unlock | InputMode::ExportConfirm => Some(&mut self.master_txt),
+            InputMode::Title => Some(&mut self.new_title),
+            InputMode::Username => Some(&mut self.new_username),
+            InputMode::Password => Some(&mut self.new_password),
+            InputMode::Totp => Some(&mut self.new_totp),
+            InputMode::Url => Some(&mut self.new_url),
+            InputMode::Notes => Some(&mut self.new_notes),
+            InputMode::Fields => Some(&mut self.new_fields),
+            InputMode::Search => Some(&mut self.search_txt),
+            InputMode::Move | InputMode::Tag => Some(&mut self.organize_txt),
+            InputMode::Import | InputMode::Export => Some(&mut self.transfer_path),
+            _ => None,
+        }
+    }
+
+    /// Types or moves the cursor in the current field.
+    pub fn edit_field(&mut self, key: &KeyEvent) {
+        // 메모와 사용자 정의 필드는 여러 줄이라 Enter로 줄을 바꾼다.
+        let multiline = matches!(self.mode, InputMode::Notes | InputMode::Fields);
+        let mut cursor = self.cursor;
+        if let Some(text) = self.field() {
+            input::edit(text, &mut cursor, key, multiline);
+        }
+        self.cursor = cursor;
+    }
+
+    /// Moves to the next or previous field of the entry form.
+    pub fn step_field(&mut self, forward: bool) {
+        const FORM: [InputMode; 8] = [
+            InputMode::Title,
+            InputMode::Username,
+            InputMode::Password,
+            InputMode::Totp,
+            InputMode::Url,
+            InputMode::Notes,
+            InputMode::Fields,
+            InputMode::Submit,
+        ];
+        let index = match FORM.iter().position(|mode| *mode == self.mode) {
+            Some(index) => index,
+            None => return,
+        };
+        let next = match forward {
+            true => (index + 1).min(FORM.len() - 1),
+            false => index.saturating_sub(1),
+        };
+        self.change_mode(FORM[next]);
     }
 
     pub fn clear_fields(&mut self) {
         self.new_title.clear();
         self.new_username.clear();
         self.new_password.clear();
+        self.new_totp.clear();
+        self.new_url.clear();
+        self.new_notes.clear();
+        self.new_fields.clear();
+        self.editing = None;
+        self.show_new_password = false;
+    }
+
+    /// Hides a revealed password, clears the clipboard and locks the vault
+    /// when their time is up.
+    pub fn tick(&mut self) {
+        if let Some(timeout) = self.lock_timeout {
+            if self.mode != InputMode::Unlock && self.last_activity.elapsed() >= timeout {
+                self.lock();
+                return;
+            }
+        }
+        if let Some((_, until)) = self.revealed {
+            if Instant::now() >= until {
+                self.revealed = None;
+            }
+        }
+        if self.clipboard.tick() {
+            self.message = "Clipboard cleared".to_owned();
+        }
+    }
+
+    /// Shows the password of the selected entry for a few seconds, or hides it again.
+    pub fn toggle_reveal(&mut self) {
+        self.revealed = match (self.selected(), self.revealed) {
+            (Some(index), Some((revealed, _))) if index == revealed => None,
+            (Some(index), _) => Some((index, Instant::now() + REVEAL_FOR)),
+            (None, _) => None,
+        };
+    }
+
+    fn is_revealed(&self, index: usize) -> bool {
+        matches!(self.revealed, Some((revealed, _)) if revealed == index)
     }
 
     pub fn insert(&mut self) {
-        let password = Password::new(
+        let totp = self.new_totp.trim();
+        if !totp.is_empty() {
+            if let Err(e) = Totp::parse(totp) {
+                self.message = format!("2FA secret: {}", e);
+                self.change_mode(InputMode::Totp);
+                return;
+            }
+        }
+        let mut password = Password::new(
             self.new_title.to_owned(),
             self.new_username.to_owned(),
             self.new_password.to_owned(),
         );
-        self.passwords.push(password);
+        password.totp = totp.to_owned();
+        password.url = self.new_url.trim().to_owned();
+        password.notes = self.new_notes.to_owned();
+        password.custom = entry::parse_fields(&self.new_fields);
+        self.revealed = None;
+        match self.editing {
+            Some(index) if index < self.passwords.len() => {
+                let old = &self.passwords[index];
+                password.folder = old.folder.to_owned();
+                password.tags = old.tags.clone();
+                password.replace(old, self.history_depth);
+                self.passwords[index] = password;
+            }
+            // 새 항목은 사이드바에서 보고 있는 폴더나 태그에 넣는다.
+            _ => {
+                match &self.filter {
+                    Filter::All => {}
+                    Filter::Folder(folder) => password.folder = folder.to_owned(),
+                    Filter::Tag(tag) => password.tags = vec![tag.to_owned()],
+                }
+                self.passwords.push(password);
+            }
+        }
         self.clear_fields();
         self.change_mode(InputMode::Normal);
+        self.save();
     }
 
-    pub fn search(&mut self) {
-        self.search_list = self
-            .passwords
+    /// The index in the vault of the entry selected in the list, or in the
+    /// search results while searching.
+    pub fn selected(&self) -> Option<usize> {
+        let index = match self.mode {
+            InputMode::Search => {
+                let hit = self.search_list.get(self.search_state.selected()?)?;
+                hit.index
+            }
+            _ => *self.visible().get(self.list_state.selected()?)?,
+        };
+        Some(index).filter(|index| *index < self.passwords.len())
+    }
+
+    /// Indices of the entries in the folder or with the tag picked in the sidebar.
+    pub fn visible(&self) -> Vec<usize> {
+        (0..self.passwords.len())
+            .filter(|index| self.filter.matches(&self.passwords[*index]))
+            .collect()
+    }
+
+    // 검색 중에는 검색 결과 안에서 움직인다.
+    fn shown_list(&mut self) -> (usize, &mut ListState) {
+        match self.mode {
+            InputMode::Search => (self.search_list.len(), &mut self.search_state),
+            _ => (self.visible().len(), &mut self.list_state),
+        }
+    }
+
+    /// Picks the next (or previous) folder or tag in the sidebar.
+    pub fn step_filter(&mut self, forward: bool) {
+        let filters = Filter::list(&self.passwords);
+        let at = filters
             .iter()
-            .filter(|password| password.title.starts_with(&self.search_txt))
-            .cloned()
-            .collect();
+            .position(|filter| *filter == self.filter)
+            .unwrap_or(0);
+        let at = match forward {
+            true => (at + 1).min(filters.len() - 1),
+            false => at.saturating_sub(1),
+        };
+        self.filter = filters[at].clone();
+        self.revealed = None;
+        self.list_state.select(match self.visible().is_empty() {
+            true => None,
+            false => Some(0),
+        });
+    }
+
+    /// Starts moving the selected entry to another folder, or editing its tags.
+    pub fn organize(&mut self, mode: InputMode) {
+        let entry = match self.selected() {
+            Some(index) => &self.passwords[index],
+            None => return,
+        };
+        self.organize_txt = match mode {
+            InputMode::Move => entry.folder.to_owned(),
+            _ => entry.tags.join(", "),
+        };
+        self.change_mode(mode);
+    }
+
+    /// Applies the folder or the tags typed in the popup.
+    pub fn apply_organize(&mut self) {
+        if let Some(index) = self.selected() {
+            let entry = &mut self.passwords[index];
+            match self.mode {
+                InputMode::Move => {
+                    entry.folder = self.organize_txt.trim().to_owned();
+                    self.message = match entry.folder.is_empty() {
+                        true => format!("Moved {} out of its folder", entry.title),
+                        false => format!("Moved {} to {}", entry.title, entry.folder),
+                    };
+                }
+                _ => {
+                    entry.tags = entry::parse_tags(&self.organize_txt);
+                    self.message = format!("Tagged {}", entry.title);
+                }
+            }
+            entry.updated_at = Local::now();
+            self.save();
+        }
+        // 옮긴 항목이 지금 필터에서 빠지면 선택이 목록 밖을 가리킬 수 있다.
+        let len = self.visible().len();
+        if self.list_state.selected().is_some_and(|i| i >= len) {
+            self.list_state.select(len.checked_sub(1));
+        }
+        self.change_mode(InputMode::List);
+    }
+
+    pub fn select_next(&mut self) {
+        let (len, state) = self.shown_list();
+        if len == 0 {
+            return;
+        }
+        let next = match state.selected() {
+            Some(index) => (index + 1).min(len - 1),
+            None => 0,
+        };
+        state.select(Some(next));
+    }
+
+    pub fn select_previous(&mut self) {
+        let (len, state) = self.shown_list();
+        if len == 0 {
+            return;
+        }
+        let previous = state.selected().unwrap_or(0).saturating_sub(1);
+        state.select(Some(previous));
+    }
+
+    /// Loads the selected entry into the form fields to edit it.
+    pub fn edit(&mut self) {
+        if let Some(index) = self.selected() {
+            let password = &self.passwords[index];
+            self.new_title = password.title.to_owned();
+            self.new_username = password.username.to_owned();
+            self.new_password = password.password.to_owned();
+            self.new_totp = password.totp.to_owned();
+            self.new_url = password.url.to_owned();
+            self.new_notes = password.notes.to_owned();
+            self.new_fields = entry::format_fields(&password.custom);
+            self.editing = Some(index);
+            self.change_mode(InputMode::Title);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if let Some(index) = self.selected() {
+            self.revealed = None;
+            let password = self.passwords.remove(index);
+            self.message = format!("Deleted {}", password.title);
+            let len = self.visible().len();
+            let at = self.list_state.selected().unwrap_or(0);
+            self.list_state.select(match len {
+                0 => None,
+                _ => Some(at.min(len - 1)),
+            });
+            self.save();
+        }
+        self.change_mode(InputMode::List);
+    }
+
+    /// Copies the username or the password of the selected entry.
+    pub fn copy(&mut self, password: bool) {
+        let (text, what) = match self.selected() {
+            Some(index) if password => {
+                let entry = &self.passwords[index];
+                match self.history_selected.and_then(|i| entry.history.get(i)) {
+                    Some(old) => (old.password.to_owned(), "Previous password"),
+                    None => (entry.password.to_owned(), "Password"),
+                }
+            }
+            Some(index) => (self.passwords[index].username.to_owned(), "Username"),
+            None => return,
+        };
+
+        self.message = match self.clipboard.copy(&text, password) {
+            Ok(()) => format!("{} copied", what),
+            Err(e) => format!("Couldn't copy: {}", e),
+        };
+    }
+
+    /// Moves the selection to the next previous password of the entry in
+    /// the detail pane, and back to the current one after the last.
+    pub fn select_history(&mut self) {
+        let len = match self.selected() {
+            Some(index) => self.passwords[index].history.len(),
+            None => return,
+        };
+        self.history_selected = match self.history_selected {
+            None if len > 0 => Some(0),
+            Some(i) if i + 1 < len => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    /// Copies the current 2FA code of the selected entry.
+    pub fn copy_totp(&mut self) {
+        let totp = match self.selected() {
+            Some(index) if !self.passwords[index].totp.is_empty() => {
+                Totp::parse(&self.passwords[index].totp)
+            }
+            Some(_) => {
+                self.message = "No 2FA secret on this entry".to_owned();
+                return;
+            }
+            None => return,
+        };
+        self.message = match totp {
+            Ok(totp) => {
+                let (code, remaining) = totp.now();
+                match self.clipboard.copy(&code, true) {
+                    Ok(()) => format!("2FA code copied, valid for {}s", remaining),
+                    Err(e) => format!("Couldn't copy: {}", e),
+                }
+            }
+            Err(e) => format!("2FA secret: {}", e),
+        };
+    }
+
+    /// Forgets the key and wipes every decrypted secret, then asks for the
+    /// master password again.
+    pub fn lock(&mut self) {
+        let agent = self.vault.take().map(|store| store.lock());
+        self.passwords.clear();
+        self.loaded.clear();
+        self.search_list.clear();
+        self.search_txt.clear();
+        self.clear_fields();
+        self.new_title.zeroize();
+        self.new_username.zeroize();
+        self.new_password.zeroize();
+        self.new_totp.zeroize();
+        self.new_url.zeroize();
+        self.new_notes.zeroize();
+        self.new_fields.zeroize();
+        self.generator.value.zeroize();
+        self.master_txt.zeroize();
+        self.master_first = None;
+        self.revealed = None;
+        self.breaches.clear();
+        self.filter = Filter::All;
+        self.list_state.select(None);
+        self.clipboard.expire();
+        self.message = match agent {
+            Some(Err(e)) => format!(
+                "Locked after inactivity, but the agent is still unlocked: {}",
+                e
+            ),
+            _ => "Locked after inactivity".to_owned(),
+        };
+        self.change_mode(InputMode::Unlock);
+    }
+
+    fn open_with(&mut self, store: Store, passwords: Vec<Password>) {
+        self.vault = Some(store);
+        self.loaded = passwords.clone();
+        self.passwords = passwords;
+        self.message.clear();
+        self.change_mode(InputMode::Normal);
+        self.check_breaches();
+    }
+
+    /// Looks up the passwords not checked yet in the breach list, if one
+    /// is configured.
+    fn check_breaches(&mut self) {
+        let path = match &self.breach_list {
+            Some(path) => path,
+            None => return,
+        };
+        let result = BreachList::open(path)
+            .and_then(|mut list| self.breaches.update(&mut list, &self.passwords));
+        if let Err(e) = result {
+            self.message = format!("Couldn't read the breach list {}: {}", path.display(), e);
+        }
+    }
+
+    /// Skips the master password when an agent already holds the vault unlocked.
+    #[cfg(unix)]
+    pub fn resume(&mut self) {
+        let client = match Client::connect(&self.agent_socket, &self.vault_path) {
+            Some(client) if client.unlocked => client,
+            _ => return,
+        };
+        if let Ok(passwords) = client.load() {
+            self.open_with(Store::Agent(client), passwords);
+            self.message = "Unlocked by the agent".to_owned();
+        }
+    }
+
+    // 에이전트가 없는 플랫폼에서는 항상 마스터 비밀번호를 묻는다.
+    #[cfg(not(unix))]
+    pub fn resume(&mut self) {}
+
+    /// Opens the vault with the typed master password. A new vault asks
+    /// for the password twice before it is created.
+    pub fn unlock(&mut self) {
+        let master = Zeroizing::new(std::mem::take(&mut self.master_txt));
+
+        if self.vault_path.exists() {
+            // 에이전트가 있으면 에이전트를 통해 열어서 다른 명령도 다시 묻지 않게 한다.
+            // 새 금고는 에이전트가 열 수 없으니 아래에서 직접 만든다.
+            #[cfg(unix)]
+            if let Some(client) = Client::connect(&self.agent_socket, &self.vault_path) {
+                match client.unlock(&master).and_then(|()| client.load()) {
+                    Ok(passwords) => self.open_with(Store::Agent(client), passwords),
+                    Err(e) => self.message = e.to_string(),
+                }
+                return;
+            }
+            match Vault::open(&self.vault_path, &master) {
+                Ok((vault, passwords)) => self.open_with(Store::Local(vault), passwords),
+                Err(e) => self.message = e.to_string(),
+            }
+            return;
+        }
+
+        match self.master_first.take() {
+            None if master.is_empty() => self.message = "Master password is empty".to_owned(),
+            None => {
+                self.master_first = Some(master);
+                self.message = "Repeat the master password".to_owned();
+            }
+            Some(first) if *first != *master => {
+                self.message = "Passwords don't match, try again".to_owned();
+            }
+            Some(_) => match Vault::create(&self.vault_path, &master) {
+                Ok(vault) => {
+                    self.open_with(Store::Local(vault), vec![]);
+                    self.save();
+                }
+                Err(e) => self.message = e.to_string(),
+            },
+        }
+    }
+
+    /// Writes the passwords to the vault, reporting a failure in the status line.
+    /// Entries saved by another client meanwhile are merged in.
+    pub fn save(&mut self) {
+        if let Some(vault) = &self.vault {
+            match vault.save(&self.loaded, &self.passwords) {
+                Ok(passwords) => {
+                    self.loaded = passwords.clone();
+                    self.passwords = passwords;
+                }
+                Err(e) => self.message = format!("Couldn't save the vault: {}", e),
+            }
+        }
+        // 다른 곳에서 지운 항목 때문에 선택이 목록 밖을 가리킬 수 있다.
+        let len = self.visible().len();
+        if self.list_state.selected().is_some_and(|i| i >= len) {
+            self.list_state.select(len.checked_sub(1));
+        }
+        self.check_breaches();
+    }
+
+    /// Adds the entries of the CSV file at `transfer_path`, skipping duplicates.
+    pub fn import(&mut self) {
+        let path = expand_home(&self.transfer_path);
+        match import::read_file(&path, None, &[]) {
+            Ok(imported) => {
+                let merged = import::merge(&mut self.passwords, imported.passwords);
+                self.message = format!("{} ({})", merged.summary(), imported.format.name());
+                // 저장에 실패하면 요약 대신 그 오류를 보여준다.
+                self.save();
+            }
+            Err(e) => self.message = format!("Couldn't import {}: {}", path.display(), e),
+        }
+        self.change_mode(InputMode::Normal);
+    }
+
+    /// Asks for the master password to export to `transfer_path`, unless
+    /// that's the vault itself or a file the user hasn't agreed to replace.
+    pub fn check_export_path(&mut self) {
+        let path = expand_home(&self.transfer_path);
+        if same_file(&path, &self.vault_path) {
+            self.message = "That's the vault itself, pick another file".to_owned();
+        } else if path.exists() && !self.export_overwrite {
+            self.export_overwrite = true;
+            self.message = format!("{} exists, Enter again to replace it", path.display());
+        } else {
+            self.message.clear();
+            self.master_txt.zeroize();
+            self.change_mode(InputMode::ExportConfirm);
+        }
+    }
+
+    /// Writes the passwords to `transfer_path` once the master password is
+    /// typed again.
+    pub fn export(&mut self) {
+        let master = Zeroizing::new(std::mem::take(&mut self.master_txt));
+        let path = expand_home(&self.transfer_path);
+        self.message = match &self.vault {
+            _ if same_file(&path, &self.vault_path) => {
+                "That's the vault itself, nothing exported".to_owned()
+            }
+            Some(store) if store.verify(&master) => {
+                match export::export(&self.passwords, &path, self.export_format, &master) {
+                    Ok(()) => format!(
+                        "Exported {} entries to {}",
+                        self.passwords.len(),
+                        path.display()
+                    ),
+                    Err(e) => format!("Couldn't export: {}", e),
+                }
+            }
+            _ => "Wrong master password, nothing exported".to_owned(),
+        };
+        self.change_mode(InputMode::Normal);
+    }
+
+    /// Runs the search again and selects the best result.
+    pub fn search(&mut self) {
+        self.search_list = search::search(&self.passwords, &self.search_txt);
+        let filter = &self.filter;
+        let passwords = &self.passwords;
+        self.search_list
+            .retain(|hit| filter.matches(&passwords[hit.index]));
+        self.search_state.select(match self.search_list.is_empty() {
+            true => None,
+            false => Some(0),
+        });
+    }
+}
+
+// 경로 앞의 ~를 홈 디렉터리로 바꾼다.
+// 심볼릭 링크나 상대 경로로 가리켜도 같은 파일로 본다.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.trim().strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path.trim()),
     }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut state = PassManager::new();
+    let cli = Cli::parse();
+    let config = Config::load(&Config::default_path())?;
+    if let Some(cmd) = cli.cmd {
+        if let Err(e) = cli::run(cmd, &config, &Master::new(cli.password_fd)) {
+            eprintln!("pass-manager: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut state = PassManager::new(config);
+    state.resume();
 
     enable_raw_mode()?;
     execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
@@ -118,6 +780,8 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     let result = run_app(&mut terminal, &mut state);
+    // 종료할 때도 복사해 둔 비밀번호를 남기지 않는다.
+    state.clipboard.expire();
 
     disable_raw_mode()?;
     execute!(
@@ -127,7 +791,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     )?;
 
     if let Err(e) = result {
-        println!("{}", e.to_string());
+        println!("{}", e);
     }
 
     Ok(())
@@ -139,112 +803,220 @@ fn run_app<B: Backend>(
 ) -> Result<(), std::io::Error> {
     loop {
         terminal.draw(|frame| ui(frame, state))?;
-        if let Key(key) = event::read()? {
-            match state.mode {
-                InputMode::Normal => match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Char('s') => {
-                        state.change_mode(InputMode::Search);
-                    }
-                    KeyCode::Char('l') => {
-                        state.change_mode(InputMode::List);
-                    }
-                    // insert key
-                    KeyCode::Char('\u{f746}') => {
-                        state.change_mode(InputMode::Title);
-                    }
-                    _ => {}
-                },
-                InputMode::Title => match key.code {
-                    KeyCode::Esc => {
-                        state.clear_fields();
-                        state.change_mode(InputMode::Normal);
-                    }
-                    KeyCode::Char(c) => {
-                        state.new_title.push(c);
-                    }
-                    KeyCode::Backspace => {
-                        state.new_title.pop();
-                    }
-                    KeyCode::Tab => {
-                        state.change_mode(InputMode::Username);
+        state.tick();
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let key = match event::read()? {
+            Key(key) => key,
+            _ => continue,
+        };
+        state.last_activity = Instant::now();
+        // 도움말은 아무 키나 누르면 닫힌다.
+        if state.show_help {
+            state.show_help = false;
+            continue;
+        }
+        let action = state.keys.action(&key);
+        match state.mode {
+            InputMode::Unlock => match key.code {
+                KeyCode::Esc => return Ok(()),
+                KeyCode::Enter => state.unlock(),
+                _ => state.edit_field(&key),
+            },
+            InputMode::Normal => match action {
+                Some(Action::Quit) => return Ok(()),
+                Some(Action::Help) => state.show_help = true,
+                Some(Action::Search) => {
+                    state.change_mode(InputMode::Search);
+                    state.search();
+                }
+                Some(Action::Audit) => state.change_mode(InputMode::Audit),
+                Some(Action::Filter) => state.change_mode(InputMode::Sidebar),
+                Some(Action::Import) => {
+                    state.transfer_path.clear();
+                    state.change_mode(InputMode::Import);
+                }
+                Some(Action::Export) => {
+                    state.transfer_path.clear();
+                    state.export_overwrite = false;
+                    state.change_mode(InputMode::Export);
+                }
+                Some(Action::ToggleList) => {
+                    if state.selected().is_none() {
+                        state.select_next();
                     }
+                    state.change_mode(InputMode::List);
+                }
+                Some(Action::NewEntry) => state.change_mode(InputMode::Title),
+                _ => {}
+            },
+            InputMode::Title
+            | InputMode::Username
+            | InputMode::Password
+            | InputMode::Totp
+            | InputMode::Url
+            | InputMode::Notes
+            | InputMode::Fields
+            | InputMode::Submit => match key.code {
+                KeyCode::Esc => {
+                    state.clear_fields();
+                    state.change_mode(InputMode::Normal);
+                }
+                KeyCode::Tab => state.step_field(true),
+                KeyCode::BackTab => state.step_field(false),
+                KeyCode::Enter if state.mode == InputMode::Submit => state.insert(),
+                _ if action == Some(Action::Generate) && state.mode == InputMode::Password => {
+                    state.generator.regenerate();
+                    state.change_mode(InputMode::Generator);
+                }
+                _ if action == Some(Action::ShowPassword) => {
+                    state.show_new_password = !state.show_new_password;
+                }
+                _ => state.edit_field(&key),
+            },
+            InputMode::Search => match key.code {
+                KeyCode::Esc => state.change_mode(InputMode::Normal),
+                KeyCode::Down => state.select_next(),
+                KeyCode::Up => state.select_previous(),
+                KeyCode::Enter => state.edit(),
+                _ if action == Some(Action::SearchCopyUsername) => state.copy(false),
+                _ if action == Some(Action::SearchCopyPassword) => state.copy(true),
+                _ => {
+                    state.edit_field(&key);
+                    state.search();
+                }
+            },
+            InputMode::List => match action {
+                Some(Action::Help) => state.show_help = true,
+                Some(Action::ToggleList) => state.change_mode(InputMode::Normal),
+                Some(Action::CopyUsername) => state.copy(false),
+                Some(Action::CopyPassword) => state.copy(true),
+                Some(Action::Edit) => state.edit(),
+                Some(Action::Reveal) => state.toggle_reveal(),
+                Some(Action::CopyTotp) => state.copy_totp(),
+                Some(Action::Filter) => state.change_mode(InputMode::Sidebar),
+                Some(Action::Move) => state.organize(InputMode::Move),
+                Some(Action::Tags) => state.organize(InputMode::Tag),
+                Some(Action::Details) if state.selected().is_some() => {
+                    state.detail_scroll = 0;
+                    state.change_mode(InputMode::Detail);
+                }
+                Some(Action::Delete) if state.selected().is_some() => {
+                    state.change_mode(InputMode::Delete);
+                }
+                _ => match key.code {
+                    KeyCode::Esc => state.change_mode(InputMode::Normal),
+                    KeyCode::Down => state.select_next(),
+                    KeyCode::Up => state.select_previous(),
                     _ => {}
                 },
-                InputMode::Username => match key.code {
-                    KeyCode::Esc => {
-                        state.clear_fields();
-                        state.change_mode(InputMode::Normal);
-                    }
-                    KeyCode::Char(c) => {
-                        state.new_username.push(c);
-                    }
-                    KeyCode::Backspace => {
-                        state.new_username.pop();
-                    }
-                    KeyCode::Tab => {
+            },
+            InputMode::Generator => {
+                let options = &mut state.generator.options;
+                match key.code {
+                    KeyCode::Esc => state.change_mode(InputMode::Password),
+                    KeyCode::Enter => {
+                        state.new_password = state.generator.value.to_owned();
                         state.change_mode(InputMode::Password);
-                    }
-                    KeyCode::BackTab => {
-                        state.change_mode(InputMode::Title);
-                    }
-                    _ => {}
-                },
-                InputMode::Password => match key.code {
-                    KeyCode::Esc => {
-                        state.clear_fields();
-                        state.change_mode(InputMode::Normal);
-                    }
-                    KeyCode::Char(c) => {
-                        state.new_password.push(c);
-                    }
-                    KeyCode::Backspace => {
-                        state.new_password.pop();
+                        continue;
                     }
                     KeyCode::Tab => {
-                        state.change_mode(InputMode::Submit);
-                    }
-                    KeyCode::BackTab => {
-                        state.change_mode(InputMode::Username);
-                    }
-                    _ => {}
-                },
-                InputMode::Submit => match key.code {
-                    KeyCode::Esc => {
-                        state.clear_fields();
-                        state.change_mode(InputMode::Normal);
-                    }
-                    KeyCode::BackTab => state.change_mode(InputMode::Password),
-                    KeyCode::Enter => {
-                        state.insert();
-                    }
-                    _ => {}
-                },
-                InputMode::Search => match key.code {
-                    KeyCode::Esc => {
-                        state.change_mode(InputMode::Normal);
-                    }
-                    KeyCode::Char(c) => {
-                        state.search_txt.push(c);
-                        state.search();
+                        options.style = match options.style {
+                            generator::Style::Password => generator::Style::Passphrase,
+                            generator::Style::Passphrase => generator::Style::Password,
+                        };
                     }
-                    KeyCode::Backspace => {
-                        state.search_txt.pop();
-                        state.search();
+                    KeyCode::Left | KeyCode::Char('-') => options.resize(false),
+                    KeyCode::Right | KeyCode::Char('+') => options.resize(true),
+                    KeyCode::Char('l') => options.toggle(Class::Lowercase),
+                    KeyCode::Char('u') => options.toggle(Class::Uppercase),
+                    KeyCode::Char('d') => options.toggle(Class::Digits),
+                    KeyCode::Char('s') => options.toggle(Class::Symbols),
+                    KeyCode::Char('a') => {
+                        options.exclude_ambiguous = !options.exclude_ambiguous;
                     }
-                    _ => {}
-                },
-                InputMode::List => match key.code {
-                    KeyCode::Char('l') => {
-                        state.change_mode(InputMode::Normal);
+                    KeyCode::Char('r') | KeyCode::Char(' ') => {}
+                    _ => continue,
+                }
+                state.generator.regenerate();
+            }
+            InputMode::Detail => match action {
+                Some(Action::Help) => state.show_help = true,
+                Some(Action::Details) => state.change_mode(InputMode::List),
+                Some(Action::CopyUsername) => state.copy(false),
+                Some(Action::CopyPassword) => state.copy(true),
+                Some(Action::Reveal) => state.toggle_reveal(),
+                Some(Action::CopyTotp) => state.copy_totp(),
+                Some(Action::Edit) => state.edit(),
+                Some(Action::History) => state.select_history(),
+                _ => match key.code {
+                    KeyCode::Esc => state.change_mode(InputMode::List),
+                    KeyCode::Down => state.detail_scroll = state.detail_scroll.saturating_add(1),
+                    KeyCode::Up => state.detail_scroll = state.detail_scroll.saturating_sub(1),
+                    KeyCode::PageDown => {
+                        state.detail_scroll = state.detail_scroll.saturating_add(10);
                     }
-                    KeyCode::Esc => {
-                        state.change_mode(InputMode::Normal);
+                    KeyCode::PageUp => {
+                        state.detail_scroll = state.detail_scroll.saturating_sub(10);
                     }
                     _ => {}
                 },
-                InputMode::Delete => {}
-            }
+            },
+            InputMode::Audit => match (action, key.code) {
+                (Some(Action::Audit | Action::Quit), _) | (_, KeyCode::Esc) => {
+                    state.change_mode(InputMode::Normal);
+                }
+                _ => {}
+            },
+            InputMode::Sidebar => match (action, key.code) {
+                (_, KeyCode::Down) => state.step_filter(true),
+                (_, KeyCode::Up) => state.step_filter(false),
+                (_, KeyCode::Enter) => state.change_mode(InputMode::List),
+                (Some(Action::Filter), _) | (_, KeyCode::Esc) => {
+                    state.change_mode(InputMode::Normal);
+                }
+                _ => {}
+            },
+            InputMode::Move | InputMode::Tag => match key.code {
+                KeyCode::Esc => state.change_mode(InputMode::List),
+                KeyCode::Enter => state.apply_organize(),
+                _ => state.edit_field(&key),
+            },
+            InputMode::Import => match key.code {
+                KeyCode::Esc => state.change_mode(InputMode::Normal),
+                KeyCode::Enter => state.import(),
+                _ => state.edit_field(&key),
+            },
+            InputMode::Export => match key.code {
+                KeyCode::Esc => state.change_mode(InputMode::Normal),
+                KeyCode::Tab => {
+                    state.export_format = match state.export_format {
+                        export::Format::Csv => export::Format::EncryptedJson,
+                        export::Format::EncryptedJson => export::Format::Csv,
+                    };
+                }
+                KeyCode::Enter if !state.transfer_path.trim().is_empty() => {
+                    state.check_export_path();
+                }
+                _ => {
+                    state.export_overwrite = false;
+                    state.edit_field(&key);
+                }
+            },
+            InputMode::ExportConfirm => match key.code {
+                KeyCode::Esc => {
+                    state.master_txt.zeroize();
+                    state.change_mode(InputMode::Normal);
+                }
+                KeyCode::Enter => state.export(),
+                _ => state.edit_field(&key),
+            },
+            InputMode::Delete => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => state.delete(),
+                KeyCode::Char('n') | KeyCode::Esc => state.change_mode(InputMode::List),
+                _ => {}
+            },
         }
     }
 }
@@ -252,13 +1024,38 @@ fn run_app<B: Backend>(
 // static dispatch
 // ui함수는 B 타입이 Backend 트레잇을 구현하는 경우에만 호출한다.
 fn ui<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager) {
+    let main_chunk = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)].as_ref())
+        .split(frame.size());
+
+    status_bar(frame, state, main_chunk[1]);
+
+    if state.mode == InputMode::Unlock {
+        unlock_section(frame, state, main_chunk[0]);
+        return;
+    }
+    if state.mode == InputMode::Audit {
+        audit_section(frame, state, main_chunk[0]);
+        return;
+    }
+
+    let sidebar_chunk = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(24), Constraint::Min(1)].as_ref())
+        .split(main_chunk[0]);
+    sidebar_section(frame, state, sidebar_chunk[0]);
+
     let parent_chunk = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-        .split(frame.size());
+        .split(sidebar_chunk[1]);
 
     let new_section_block = Block::default()
-        .title("New Password")
+        .title(match state.editing {
+            Some(_) => "Edit Password",
+            None => "New Password",
+        })
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
     frame.render_widget(new_section_block, parent_chunk[0]);
@@ -270,7 +1067,407 @@ fn ui<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager) {
         .border_type(BorderType::Rounded);
 
     frame.render_widget(list_section_block, parent_chunk[1]);
-    list_section(frame, state, parent_chunk[1]);
+    if state.mode == InputMode::Detail {
+        detail_section(frame, state, parent_chunk[1]);
+    } else {
+        list_section(frame, state, parent_chunk[1]);
+    }
+
+    if state.mode == InputMode::Delete {
+        delete_popup(frame, state, main_chunk[0]);
+    }
+    if state.mode == InputMode::Generator {
+        generator_popup(frame, state, main_chunk[0]);
+    }
+    if matches!(
+        state.mode,
+        InputMode::Import | InputMode::Export | InputMode::ExportConfirm
+    ) {
+        transfer_popup(frame, state, main_chunk[0]);
+    }
+    if matches!(state.mode, InputMode::Move | InputMode::Tag) {
+        organize_popup(frame, state, main_chunk[0]);
+    }
+    if state.show_help {
+        help_popup(frame, state, main_chunk[0]);
+    }
+}
+
+/// Puts the terminal cursor at `column` and `row` of the text inside the
+/// bordered `area`.
+fn place_cursor<B: Backend>(frame: &mut Frame<B>, area: Rect, (column, row): (u16, u16)) {
+    let x = (area.x + 1 + column).min(area.right().saturating_sub(2));
+    let y = (area.y + 1 + row).min(area.bottom().saturating_sub(2));
+    frame.set_cursor(x, y);
+}
+
+/// The key bindings in use, with the keys that can't be changed.
+fn help_popup<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Spans> = state
+        .keys
+        .help()
+        .into_iter()
+        .map(|(keys, description)| {
+            Spans::from(vec![
+                Span::styled(format!("{:15}", keys), bold),
+                Span::raw(description),
+            ])
+        })
+        .collect();
+    lines.push(Spans::default());
+    for (keys, description) in [
+        ("Up/Down", "Select an entry, a folder or a search result"),
+        ("Tab/Shift+Tab", "Go to the next/previous field"),
+        (
+            "Left/Right",
+            "Move the cursor, Home/End to the start/end of the line",
+        ),
+        ("Esc", "Go back, or leave the form without saving"),
+        ("y/n", "Confirm or cancel deleting an entry"),
+        (
+            "l/u/d/s/a",
+            "In the generator, toggle a-z/A-Z/0-9/symbols/ambiguous",
+        ),
+        (
+            "Left/Right",
+            "In the generator, change the length, r: generate again",
+        ),
+        (
+            "Tab",
+            "In the generator, switch between password and passphrase",
+        ),
+    ] {
+        lines.push(Spans::from(vec![
+            Span::styled(format!("{:15}", keys), bold),
+            Span::raw(description),
+        ]));
+    }
+
+    let height = lines.len() as u16 + 2;
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .title("Key bindings (any key: close)")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded),
+    );
+    let area = centered_rect(76, height, area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// The folders and tags in use, to filter the list.
+fn sidebar_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let filters = Filter::list(&state.passwords);
+    let items: Vec<ListItem> = filters
+        .iter()
+        .map(|filter| {
+            let count = state
+                .passwords
+                .iter()
+                .filter(|entry| filter.matches(entry))
+                .count();
+            let name = match filter {
+                Filter::All => "All".to_owned(),
+                Filter::Folder(folder) => format!("▸ {}", folder),
+                Filter::Tag(tag) => format!("#{}", tag),
+            };
+            ListItem::new(format!("{} ({})", name, count))
+        })
+        .collect();
+    state
+        .sidebar_state
+        .select(filters.iter().position(|filter| *filter == state.filter));
+
+    let sidebar = List::new(items)
+        .block(
+            Block::default()
+                .title("Folders & tags")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .highlight_symbol("->")
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .style(match state.mode {
+            InputMode::Sidebar => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        });
+    frame.render_stateful_widget(sidebar, area, &mut state.sidebar_state);
+}
+
+fn organize_popup<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let title = match state.selected() {
+        Some(index) => state.passwords[index].title.to_owned(),
+        None => return,
+    };
+    let (block_title, help) = match state.mode {
+        InputMode::Move => ("Move to folder", "Empty for no folder."),
+        _ => ("Tags", "Separate tags with commas."),
+    };
+    let popup = Paragraph::new(format!(
+        "{}: {}\n\n{}\nEnter: save  Esc: cancel",
+        title, state.organize_txt, help
+    ))
+    .wrap(Wrap { trim: false })
+    .block(
+        Block::default()
+            .title(block_title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded),
+    )
+    .style(Style::default().fg(Color::Yellow));
+    let area = centered_rect(50, 7, area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+    let (column, _) = input::position(&state.organize_txt, state.cursor);
+    place_cursor(frame, area, (title.chars().count() as u16 + 2 + column, 0));
+}
+
+fn transfer_popup<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let (title, text) = match state.mode {
+        InputMode::Import => (
+            "Import",
+            format!(
+                "CSV file: {}\n\nBitwarden, 1Password, KeePass and Chrome exports are\nrecognized. Entries already in the vault are skipped.\n\nEnter: import  Esc: cancel",
+                state.transfer_path
+            ),
+        ),
+        InputMode::Export => (
+            "Export",
+            format!(
+                "File: {}\nFormat: {} (Tab: change)\n\n{}  Esc: cancel",
+                state.transfer_path,
+                state.export_format.name(),
+                match state.export_overwrite {
+                    true => "The file exists. Enter: replace it",
+                    false => "Enter: continue",
+                }
+            ),
+        ),
+        _ => (
+            "Export: confirm",
+            format!(
+                "Master password: {}\n\nEnter: export to {}  Esc: cancel",
+                "•".repeat(state.master_txt.chars().count()),
+                state.transfer_path
+            ),
+        ),
+    };
+    let popup = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(Style::default().fg(Color::Yellow));
+    let area = centered_rect(60, 9, area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+    let (label, text) = match state.mode {
+        InputMode::Import => ("CSV file: ", &state.transfer_path),
+        InputMode::Export => ("File: ", &state.transfer_path),
+        _ => ("Master password: ", &state.master_txt),
+    };
+    let (column, _) = input::position(text, state.cursor);
+    place_cursor(frame, area, (label.chars().count() as u16 + column, 0));
+}
+
+fn generator_popup<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let options = &state.generator.options;
+    let check = |on: bool| if on { "[x]" } else { "[ ]" };
+    let size = match options.style {
+        generator::Style::Password => format!("Length: {}", options.length),
+        generator::Style::Passphrase => format!("Words: {}", options.words),
+    };
+    let classes = match options.style {
+        generator::Style::Password => format!(
+            "{} l: a-z  {} u: A-Z  {} d: 0-9  {} s: symbols\n{} a: exclude ambiguous",
+            check(options.lowercase),
+            check(options.uppercase),
+            check(options.digits),
+            check(options.symbols),
+            check(options.exclude_ambiguous),
+        ),
+        generator::Style::Passphrase => "EFF diceware wordlist\n".to_owned(),
+    };
+    let bits = options.entropy();
+    let text = format!(
+        "{}\n\n{} (Left/Right)\n{}\nEntropy: {:.0} bits, {}\n\nTab: password/passphrase  r: again\nEnter: use it  Esc: cancel",
+        state.generator.value,
+        size,
+        classes,
+        bits,
+        generator::strength(bits),
+    );
+
+    let popup = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(match options.style {
+                    generator::Style::Password => "Generate password",
+                    generator::Style::Passphrase => "Generate passphrase",
+                })
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(Style::default().fg(Color::Yellow));
+    let area = centered_rect(60, 13, area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+fn delete_popup<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let title = match state.selected() {
+        Some(index) => state.passwords[index].title.to_owned(),
+        None => return,
+    };
+    let popup = Paragraph::new(format!("Delete {}?\n\ny: delete   n: cancel", title))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .title("Confirm")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(Style::default().fg(Color::Red));
+    let area = centered_rect(40, 5, area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+fn status_bar<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Length(30)].as_ref())
+        .split(area);
+
+    let message = Paragraph::new(state.message.to_owned());
+    frame.render_widget(message, chunks[0]);
+
+    if let Some(remaining) = state.clipboard.remaining() {
+        // 남은 시간을 올림해서 0초가 보이지 않게 한다.
+        let seconds = (remaining.as_millis() as u64).div_ceil(1000);
+        let countdown = Paragraph::new(format!("Clipboard clears in {}s", seconds))
+            .alignment(Alignment::Right)
+            .style(Style::default().fg(Color::Yellow));
+        frame.render_widget(countdown, chunks[1]);
+    }
+}
+
+fn audit_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let report = Report::new(
+        &state.passwords,
+        state.max_password_age,
+        Local::now(),
+        &state.breaches,
+    );
+
+    let rows: Vec<Row> = report
+        .findings
+        .iter()
+        .map(|finding| {
+            let strength = generator::strength(finding.bits);
+            let color = match strength {
+                "weak" => Color::Red,
+                "fair" => Color::Yellow,
+                _ => Color::Green,
+            };
+            Row::new(vec![
+                Cell::from(finding.title.to_owned()),
+                Cell::from(format!("{:.0}", finding.bits)),
+                Cell::from(strength).style(Style::default().fg(color)),
+                Cell::from(finding.issues().join("; ")),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(vec!["Title", "Bits", "Strength", "Issues"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(
+            Block::default()
+                .title(format!(
+                    "Audit: {} of {} entries need attention (Esc: back)",
+                    report.issue_count(),
+                    report.findings.len()
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .column_spacing(2)
+        .widths(
+            [
+                Constraint::Percentage(25),
+                Constraint::Length(5),
+                Constraint::Length(12),
+                Constraint::Percentage(60),
+            ]
+            .as_ref(),
+        );
+    frame.render_widget(table, area);
+}
+
+fn unlock_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(40),
+                Constraint::Length(3),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(25),
+                Constraint::Percentage(50),
+                Constraint::Percentage(25),
+            ]
+            .as_ref(),
+        )
+        .split(rows[1]);
+
+    let title = if state.vault_path.exists() {
+        "Master password (Enter: unlock, Esc: quit)"
+    } else if state.master_first.is_some() {
+        "Repeat the new master password"
+    } else {
+        "New master password (Enter: create the vault)"
+    };
+    let master_input = Paragraph::new("•".repeat(state.master_txt.chars().count()))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(Style::default().fg(Color::Yellow));
+    frame.render_widget(master_input, columns[1]);
+    place_cursor(
+        frame,
+        columns[1],
+        input::position(&state.master_txt, state.cursor),
+    );
 }
 
 fn new_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
@@ -284,12 +1481,25 @@ fn new_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area:
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(5),
+                Constraint::Length(5),
+                Constraint::Length(3),
             ]
             .as_ref(),
         )
         .split(area);
 
-    let desc = Paragraph::new(APP_KEYS_DESC);
+    let keys = &state.keys;
+    let desc = Paragraph::new(format!(
+        "{}: add an entry   {}: list   {}: search\n{}: all key bindings   {}: quit",
+        keys.keys(Action::NewEntry),
+        keys.keys(Action::ToggleList),
+        keys.keys(Action::Search),
+        keys.keys(Action::Help),
+        keys.keys(Action::Quit),
+    ))
+    .wrap(Wrap { trim: false });
     frame.render_widget(desc, new_section_chunk[0]);
 
     let title_input = Paragraph::new(state.new_title.to_owned())
@@ -318,10 +1528,22 @@ fn new_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area:
         });
     frame.render_widget(username_input, new_section_chunk[2]);
 
-    let password_input = Paragraph::new(state.new_password.to_owned())
+    let password_text = match state.show_new_password {
+        true => state.new_password.to_owned(),
+        false => "•".repeat(state.new_password.chars().count()),
+    };
+    let password_input = Paragraph::new(password_text)
         .block(
             Block::default()
-                .title("Password")
+                .title(format!(
+                    "Password ({}: {}, {}: generate)",
+                    state.keys.keys(Action::ShowPassword),
+                    match state.show_new_password {
+                        true => "hide",
+                        false => "show",
+                    },
+                    state.keys.keys(Action::Generate),
+                ))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded),
         )
@@ -331,6 +1553,76 @@ fn new_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area:
         });
     frame.render_widget(password_input, new_section_chunk[3]);
 
+    let totp_text = match state.show_new_password {
+        true => state.new_totp.to_owned(),
+        false => "•".repeat(state.new_totp.chars().count()),
+    };
+    let totp_input = Paragraph::new(totp_text)
+        .block(
+            Block::default()
+                .title("2FA secret (base32 or otpauth:// URI)")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(match state.mode {
+            InputMode::Totp => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        });
+    frame.render_widget(totp_input, new_section_chunk[4]);
+
+    let url_input = Paragraph::new(state.new_url.to_owned())
+        .block(
+            Block::default()
+                .title("URL")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(match state.mode {
+            InputMode::Url => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        });
+    frame.render_widget(url_input, new_section_chunk[5]);
+
+    let notes_input = Paragraph::new(state.new_notes.to_owned())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title("Notes")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(match state.mode {
+            InputMode::Notes => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        });
+    frame.render_widget(notes_input, new_section_chunk[6]);
+
+    // 비밀 값은 비밀번호처럼 보이게 했을 때만 그대로 보여준다.
+    let show = state.show_new_password;
+    let mask_fields = |text: &str| {
+        text.split('\n')
+            .map(|line| match (line.starts_with('!'), line.split_once(':')) {
+                (true, Some((name, value))) if !show => {
+                    format!("{}: {}", name, "•".repeat(value.trim().chars().count()))
+                }
+                _ => line.to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let fields_input = Paragraph::new(mask_fields(&state.new_fields))
+        .block(
+            Block::default()
+                .title("Fields (name: value, !name for a secret)")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .style(match state.mode {
+            InputMode::Fields => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        });
+    frame.render_widget(fields_input, new_section_chunk[7]);
+
     let submit_btn = Paragraph::new("Submit")
         .alignment(Alignment::Center)
         .block(
@@ -342,27 +1634,220 @@ fn new_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area:
             InputMode::Submit => Style::default().fg(Color::Yellow),
             _ => Style::default(),
         });
-    frame.render_widget(submit_btn, new_section_chunk[4]);
+    frame.render_widget(submit_btn, new_section_chunk[8]);
+
+    // 가린 값도 글자 수는 같아서 원래 값으로 커서 위치를 구한다.
+    let (chunk, position) = match state.mode {
+        InputMode::Title => (1, input::position(&state.new_title, state.cursor)),
+        InputMode::Username => (2, input::position(&state.new_username, state.cursor)),
+        InputMode::Password => (3, input::position(&state.new_password, state.cursor)),
+        InputMode::Totp => (4, input::position(&state.new_totp, state.cursor)),
+        InputMode::Url => (5, input::position(&state.new_url, state.cursor)),
+        InputMode::Notes => (6, input::position(&state.new_notes, state.cursor)),
+        InputMode::Fields => {
+            let before = mask_fields(input::before(&state.new_fields, state.cursor));
+            (7, input::position(&before, before.chars().count()))
+        }
+        _ => return,
+    };
+    place_cursor(frame, new_section_chunk[chunk], position);
 }
 
-fn list_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
-    let list_to_show = if state.mode == InputMode::Search {
-        state.search_list.to_owned()
-    } else {
-        state.passwords.to_owned()
+/// Formats a date of an entry, which may be missing from older vaults.
+fn date(at: DateTime<Local>, format: &str) -> String {
+    match at == entry::unknown_date() {
+        true => "unknown".to_owned(),
+        false => at.format(format).to_string(),
+    }
+}
+
+fn detail_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let index = match state.selected() {
+        Some(index) => index,
+        None => return,
     };
-    let items: Vec<ListItem> = list_to_show
-        .into_iter()
-        .map(|item| match state.mode {
-            InputMode::List => ListItem::new(format!(
-                "{}: {} - {}",
-                item.title.to_owned(),
-                item.username.to_owned(),
-                item.password.to_owned()
+    let entry = &state.passwords[index];
+    let revealed = state.is_revealed(index);
+    let secret = |value: &str| match revealed {
+        true => value.to_owned(),
+        false => MASK.to_owned(),
+    };
+    let label = |name: &str| {
+        Span::styled(
+            format!("{}: ", name),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    };
+    let date_format = "%Y-%m-%d %H:%M";
+
+    let mut lines = vec![
+        Spans::from(vec![label("Title"), Span::raw(entry.title.to_owned())]),
+        Spans::from(vec![
+            label("Username"),
+            Span::raw(entry.username.to_owned()),
+        ]),
+        Spans::from(vec![label("Password"), Span::raw(secret(&entry.password))]),
+        Spans::from(vec![label("URL"), Span::raw(entry.url.to_owned())]),
+        Spans::from(vec![label("Folder"), Span::raw(entry.folder.to_owned())]),
+        Spans::from(vec![label("Tags"), Span::raw(entry.tags.join(", "))]),
+        Spans::from(vec![
+            label("Created"),
+            Span::raw(date(entry.created_at, date_format)),
+        ]),
+        Spans::from(vec![
+            label("Modified"),
+            Span::raw(date(entry.updated_at, date_format)),
+        ]),
+        Spans::from(vec![
+            label("Password changed"),
+            Span::raw(date(entry.changed_at, date_format)),
+        ]),
+    ];
+    let breached = state.breaches.count(&entry.password);
+    if breached > 0 {
+        // 비밀번호 바로 아래에 보여준다.
+        lines.insert(
+            3,
+            Spans::from(Span::styled(
+                format!("Seen {} times in breaches, change it", breached),
+                Style::default().fg(Color::Red),
             )),
-            _ => ListItem::new(Span::from(item.title)),
+        );
+    }
+    if !entry.history.is_empty() {
+        lines.push(Spans::default());
+        lines.push(Spans::from(label("Previous passwords")));
+        for (i, old) in entry.history.iter().enumerate() {
+            let selected = state.history_selected == Some(i);
+            let style = match selected {
+                true => Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+                false => Style::default(),
+            };
+            lines.push(Spans::from(Span::styled(
+                format!(
+                    "{}{} to {}  {}",
+                    if selected { "->" } else { "  " },
+                    date(old.changed_at, date_format),
+                    old.replaced_at.format(date_format),
+                    secret(&old.password)
+                ),
+                style,
+            )));
+        }
+    }
+    if !entry.custom.is_empty() {
+        lines.push(Spans::default());
+        for field in &entry.custom {
+            let value = match field.secret {
+                true => secret(&field.value),
+                false => field.value.to_owned(),
+            };
+            lines.push(Spans::from(vec![label(&field.name), Span::raw(value)]));
+        }
+    }
+    if !entry.notes.is_empty() {
+        lines.push(Spans::default());
+        lines.push(Spans::from(label("Notes")));
+        lines.extend(entry.notes.lines().map(|line| Spans::from(line.to_owned())));
+    }
+
+    let detail = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((state.detail_scroll, 0))
+        .block(
+            Block::default()
+                .title(format!(
+                    "Up/Down: scroll  {}: history  {}/{}/{}: copy  {}: reveal  {}: edit  Esc: back",
+                    state.keys.keys(Action::History),
+                    state.keys.keys(Action::CopyUsername),
+                    state.keys.keys(Action::CopyPassword),
+                    state.keys.keys(Action::CopyTotp),
+                    state.keys.keys(Action::Reveal),
+                    state.keys.keys(Action::Edit),
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        );
+    let totp = match entry.totp.is_empty() {
+        true => None,
+        false => Some(Totp::parse(&entry.totp)),
+    };
+    let chunks = Layout::default()
+        .margin(2)
+        .constraints(match totp {
+            Some(_) => [Constraint::Length(3), Constraint::Min(1)].as_ref(),
+            None => [Constraint::Length(0), Constraint::Min(1)].as_ref(),
         })
-        .collect();
+        .split(area);
+    frame.render_widget(detail, chunks[1]);
+
+    // 코드가 바뀌기까지 남은 시간을 게이지로 보여준다.
+    let block = Block::default()
+        .title("2FA code")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+    match totp {
+        Some(Ok(totp)) => {
+            let (code, remaining) = totp.now();
+            let (first, second) = code.split_at(code.len() / 2);
+            let gauge = Gauge::default()
+                .block(block)
+                .gauge_style(Style::default().fg(match remaining {
+                    0..=5 => Color::Red,
+                    _ => Color::Green,
+                }))
+                .ratio(remaining as f64 / totp.period as f64)
+                .label(format!("{} {}  {}s", first, second, remaining));
+            frame.render_widget(gauge, chunks[0]);
+        }
+        Some(Err(e)) => {
+            let error = Paragraph::new(e.to_string())
+                .block(block)
+                .style(Style::default().fg(Color::Red));
+            frame.render_widget(error, chunks[0]);
+        }
+        None => {}
+    }
+}
+
+fn list_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area: Rect) {
+    let items: Vec<ListItem> = match state.mode {
+        InputMode::Search => state
+            .search_list
+            .iter()
+            .filter_map(|hit| Some(search_item(state.passwords.get(hit.index)?, hit)))
+            .collect(),
+        InputMode::List | InputMode::Delete | InputMode::Move | InputMode::Tag => state
+            .visible()
+            .into_iter()
+            .map(|index| {
+                let item = &state.passwords[index];
+                let mut spans = vec![Span::raw(format!(
+                    "{}: {} - {}",
+                    item.title,
+                    item.username,
+                    match state.is_revealed(index) {
+                        true => item.password.as_str(),
+                        false => MASK,
+                    }
+                ))];
+                spans.extend(breach_mark(state.breaches.count(&item.password)));
+                ListItem::new(Spans::from(spans))
+            })
+            .collect(),
+        _ => state
+            .visible()
+            .into_iter()
+            .map(|index| {
+                let item = &state.passwords[index];
+                let mut spans = vec![Span::raw(item.title.to_owned())];
+                spans.extend(breach_mark(state.breaches.count(&item.password)));
+                ListItem::new(Spans::from(spans))
+            })
+            .collect(),
+    };
 
     let list_chunks = Layout::default()
         .margin(2)
@@ -382,10 +1867,62 @@ fn list_section<B: Backend>(frame: &mut Frame<B>, state: &mut PassManager, area:
             _ => Style::default(),
         });
     frame.render_widget(search_input, list_chunks[0]);
+    if state.mode == InputMode::Search {
+        let position = input::position(&state.search_txt, state.cursor);
+        place_cursor(frame, list_chunks[0], position);
+    }
 
     let list = List::new(items)
         .block(Block::default())
         .highlight_symbol("->")
         .highlight_style(Style::default().add_modifier(Modifier::BOLD));
-    frame.render_stateful_widget(list, list_chunks[1], &mut state.list_state);
+    if state.mode == InputMode::Search {
+        frame.render_stateful_widget(list, list_chunks[1], &mut state.search_state);
+    } else {
+        frame.render_stateful_widget(list, list_chunks[1], &mut state.list_state);
+    }
+}
+
+/// Flags an entry whose password was seen in breaches.
+fn breach_mark(count: u64) -> Option<Span<'static>> {
+    match count {
+        0 => None,
+        _ => Some(Span::styled(" ! breached", Style::default().fg(Color::Red))),
+    }
+}
+
+/// A search result with the matched characters highlighted. A match outside
+/// the title is shown after it, with the name of the field.
+fn search_item<'a>(item: &'a Password, hit: &Hit) -> ListItem<'a> {
+    let highlight = |text: &'a str| -> Vec<Span<'a>> {
+        text.chars()
+            .enumerate()
+            .map(|(i, c)| match hit.positions.contains(&i) {
+                true => Span::styled(
+                    c.to_string(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                false => Span::raw(c.to_string()),
+            })
+            .collect()
+    };
+
+    let mut spans = match hit.field {
+        Field::Title => return ListItem::new(Spans::from(highlight(&item.title))),
+        _ => vec![Span::raw(item.title.as_str())],
+    };
+    // 메모는 첫 줄만 보여준다.
+    let text = match hit.field {
+        Field::Username => item.username.as_str(),
+        Field::Url => item.url.as_str(),
+        _ => item.notes.lines().next().unwrap_or_default(),
+    };
+    spans.push(Span::styled(
+        format!("  {}: ", hit.field.name()),
+        Style::default().fg(Color::DarkGray),
+    ));
+    spans.extend(highlight(text));
+    ListItem::new(Spans::from(spans))
 }
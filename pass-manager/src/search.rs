@@ -0,0 +1,201 @@
+//! Fuzzy search over the entries of the vault.
+
+use crate::entry::Password;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Username,
+    Url,
+    Notes,
+}
+
+impl Field {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Username => "username",
+            Self::Url => "url",
+            Self::Notes => "notes",
+        }
+    }
+
+    // 제목에서 찾은 결과를 먼저 보여준다.
+    fn bonus(self) -> i64 {
+        match self {
+            Self::Title => 20,
+            Self::Username => 10,
+            Self::Url => 5,
+            Self::Notes => 0,
+        }
+    }
+}
+
+/// Where a pattern matched in a text.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match {
+    pub score: i64,
+    /// Char indices of the matched characters
+    pub positions: Vec<usize>,
+}
+
+/// Matches the characters of `pattern` in order, ignoring case, anywhere in
+/// `text`. Consecutive characters and characters at the start of a word
+/// score higher, every skipped character lowers the score.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Match> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    if pattern.is_empty() {
+        return Some(Match {
+            score: 0,
+            positions: vec![],
+        });
+    }
+
+    // 앞에서부터 처음으로 모두 맞는 끝을 찾고, 거기서 거꾸로 훑어 가장 짧은 구간을 고른다.
+    let mut next = 0;
+    let mut end = None;
+    for (i, c) in lower.iter().enumerate() {
+        if *c == pattern[next] {
+            next += 1;
+            if next == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut wanted = pattern.iter().rev().peekable();
+    for i in (0..=end).rev() {
+        match wanted.peek() {
+            Some(c) if **c == lower[i] => {
+                positions.push(i);
+                wanted.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    positions.reverse();
+
+    let mut score = 0;
+    for (n, &i) in positions.iter().enumerate() {
+        score += 16;
+        if n > 0 && positions[n - 1] + 1 == i {
+            score += 8;
+        }
+        let word_start = i == 0
+            || !text[i - 1].is_alphanumeric()
+            || (text[i - 1].is_lowercase() && text[i].is_uppercase());
+        if word_start {
+            score += 10;
+        }
+    }
+    if positions[0] == 0 {
+        score += 15;
+    }
+    let span = positions[positions.len() - 1] - positions[0] + 1;
+    score -= 3 * (span - positions.len()) as i64;
+
+    Some(Match { score, positions })
+}
+
+/// An entry found by a search, with the field it matched best.
+#[derive(Debug)]
+pub struct Hit {
+    /// Index of the entry in the vault
+    pub index: usize,
+    pub field: Field,
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// Searches every field of `passwords` and returns the matching entries,
+/// best first.
+pub fn search(passwords: &[Password], pattern: &str) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = passwords
+        .iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            [
+                (Field::Title, &entry.title),
+                (Field::Username, &entry.username),
+                (Field::Url, &entry.url),
+                (Field::Notes, &entry.notes),
+            ]
+            .into_iter()
+            .filter_map(|(field, text)| {
+                fuzzy_match(pattern, text).map(|found| Hit {
+                    index,
+                    field,
+                    score: found.score + field.bonus(),
+                    positions: found.positions,
+                })
+            })
+            .max_by_key(|hit| hit.score)
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| passwords[a.index].title.cmp(&passwords[b.index].title))
+    });
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, username: &str, notes: &str) -> Password {
+        let mut entry = Password::new(title.to_owned(), username.to_owned(), "pw".to_owned());
+        entry.notes = notes.to_owned();
+        entry
+    }
+
+    #[test]
+    fn matches_in_order_ignoring_case() {
+        let found = fuzzy_match("gml", "GMail").unwrap();
+        assert_eq!(found.positions, vec![0, 1, 4]);
+        assert!(fuzzy_match("lmg", "GMail").is_none());
+        assert!(fuzzy_match("", "anything").unwrap().positions.is_empty());
+    }
+
+    #[test]
+    fn picks_the_tightest_match() {
+        let found = fuzzy_match("ab", "a_x_ab").unwrap();
+        assert_eq!(found.positions, vec![4, 5]);
+    }
+
+    #[test]
+    fn ranks_prefixes_and_word_starts_higher() {
+        let prefix = fuzzy_match("git", "github").unwrap().score;
+        let word = fuzzy_match("git", "my-github").unwrap().score;
+        let scattered = fuzzy_match("git", "going to it").unwrap().score;
+        assert!(prefix > word);
+        assert!(word > scattered);
+    }
+
+    #[test]
+    fn searches_every_field() {
+        let passwords = vec![
+            entry("bank", "alice", ""),
+            entry("mail", "bob", "backup codes in the bank"),
+            entry("banking app", "carol", ""),
+        ];
+        let hits = search(&passwords, "bank");
+        let order: Vec<usize> = hits.iter().map(|hit| hit.index).collect();
+        assert_eq!(order, vec![0, 2, 1]);
+        assert_eq!(hits[2].field, Field::Notes);
+
+        let hits = search(&passwords, "bob");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].field, Field::Username);
+    }
+}
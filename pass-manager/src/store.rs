@@ -0,0 +1,78 @@
+//! Where an unlocked vault is saved: directly, or through the agent on
+//! Unix.
+
+use std::io;
+use thiserror::Error;
+
+#[cfg(unix)]
+use crate::agent::{AgentError, Client};
+use crate::entry::{self, Password};
+use crate::vault::{Vault, VaultError};
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum StoreError {
+    #[error("{0}")]
+    Vault(#[from] VaultError),
+    #[cfg(unix)]
+    #[error("{0}")]
+    Agent(#[from] AgentError),
+}
+
+pub enum Store {
+    Local(Vault),
+    #[cfg(unix)]
+    Agent(Client),
+}
+
+impl Store {
+    /// Saves `passwords`, edited from `base`, without dropping what another
+    /// client saved in the meantime. Returns the entries now in the vault.
+    pub fn save(
+        &self,
+        base: &[Password],
+        passwords: &[Password],
+    ) -> Result<Vec<Password>, StoreError> {
+        match self {
+            Self::Local(vault) => Ok(save_merged(vault, base, passwords)?),
+            #[cfg(unix)]
+            Self::Agent(client) => Ok(client.save(base, passwords)?),
+        }
+    }
+
+    /// Locks the agent too, so the vault isn't left open behind the TUI.
+    pub fn lock(&self) -> Result<(), StoreError> {
+        match self {
+            Self::Local(_) => Ok(()),
+            #[cfg(unix)]
+            Self::Agent(client) => Ok(client.lock()?),
+        }
+    }
+
+    /// Whether `master` is the master password.
+    pub fn verify(&self, master: &str) -> bool {
+        match self {
+            Self::Local(vault) => vault.verify(master),
+            #[cfg(unix)]
+            Self::Agent(client) => client.unlock(master).is_ok(),
+        }
+    }
+}
+
+/// Saves `passwords` over what the vault holds now, keeping the changes
+/// other clients saved since `base` was loaded. Returns what was saved.
+pub(crate) fn save_merged(
+    vault: &Vault,
+    base: &[Password],
+    passwords: &[Password],
+) -> Result<Vec<Password>, VaultError> {
+    let current = match vault.load() {
+        Ok(current) => current,
+        // 만들기만 하고 아직 저장하지 않은 금고
+        Err(VaultError::Io(e)) if e.kind() == io::ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e),
+    };
+    let merged = entry::merge(base, passwords, &current);
+    vault.save(&merged)?;
+    Ok(merged)
+}
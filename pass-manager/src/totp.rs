@@ -0,0 +1,227 @@
+//! Time-based one-time passwords (RFC 6238) for the 2FA secrets of entries.
+
+use hmac::{Hmac, KeyInit, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const BASE32: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TotpError {
+    #[error("The secret is empty")]
+    Empty,
+    #[error("The secret isn't valid base32")]
+    Base32,
+    #[error("Not a TOTP URI: {0}")]
+    Uri(String),
+    #[error("Unsupported {0}")]
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug)]
+pub struct Totp {
+    secret: Zeroizing<Vec<u8>>,
+    pub algorithm: Algorithm,
+    pub digits: u32,
+    /// Seconds each code is valid for
+    pub period: u64,
+}
+
+impl Totp {
+    /// Reads a base32 secret or an `otpauth://totp/...` URI, as shown under
+    /// the QR code of most sites.
+    pub fn parse(text: &str) -> Result<Self, TotpError> {
+        let text = text.trim();
+        let query = match text.strip_prefix("otpauth://") {
+            Some(rest) => match rest.split_once('?') {
+                Some((kind, query)) if kind.to_ascii_lowercase().starts_with("totp/") => query,
+                _ => return Err(TotpError::Uri(text.to_owned())),
+            },
+            None => {
+                return Ok(Self {
+                    secret: decode_base32(text)?,
+                    algorithm: Algorithm::Sha1,
+                    digits: 6,
+                    period: 30,
+                })
+            }
+        };
+
+        let mut totp = Self {
+            secret: Zeroizing::new(vec![]),
+            algorithm: Algorithm::Sha1,
+            digits: 6,
+            period: 30,
+        };
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let unsupported = || TotpError::Unsupported(format!("{} {}", key, value));
+            match key {
+                "secret" => totp.secret = decode_base32(value)?,
+                "algorithm" => {
+                    totp.algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => Algorithm::Sha1,
+                        "SHA256" => Algorithm::Sha256,
+                        "SHA512" => Algorithm::Sha512,
+                        _ => return Err(unsupported()),
+                    }
+                }
+                "digits" => {
+                    totp.digits = value
+                        .parse()
+                        .ok()
+                        .filter(|digits| (6..=8).contains(digits))
+                        .ok_or_else(unsupported)?;
+                }
+                "period" => {
+                    totp.period = value
+                        .parse()
+                        .ok()
+                        .filter(|period| *period > 0)
+                        .ok_or_else(unsupported)?;
+                }
+                _ => {}
+            }
+        }
+        if totp.secret.is_empty() {
+            return Err(TotpError::Empty);
+        }
+        Ok(totp)
+    }
+
+    /// The code for `time`, in seconds since the Unix epoch.
+    pub fn code_at(&self, time: u64) -> String {
+        let counter = time / self.period;
+        let hash = match self.algorithm {
+            Algorithm::Sha1 => hmac::<Hmac<Sha1>>(&self.secret, counter),
+            Algorithm::Sha256 => hmac::<Hmac<Sha256>>(&self.secret, counter),
+            Algorithm::Sha512 => hmac::<Hmac<Sha512>>(&self.secret, counter),
+        };
+
+        // RFC 4226의 동적 절단: 마지막 바이트의 하위 4비트가 가리키는 곳에서 4바이트를 읽는다.
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let value = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        format!(
+            "{:0width$}",
+            value % 10u32.pow(self.digits),
+            width = self.digits as usize
+        )
+    }
+
+    /// The current code and the seconds until it changes.
+    pub fn now(&self) -> (String, u64) {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        (self.code_at(time), self.period - time % self.period)
+    }
+}
+
+fn hmac<M: Mac + KeyInit>(key: &[u8], counter: u64) -> Vec<u8> {
+    let mut mac = <M as KeyInit>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(&counter.to_be_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Decodes RFC 4648 base32, ignoring case, spaces and padding.
+fn decode_base32(text: &str) -> Result<Zeroizing<Vec<u8>>, TotpError> {
+    let mut bytes = Zeroizing::new(Vec::with_capacity(text.len() * 5 / 8));
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes().filter(|c| !matches!(c, b' ' | b'=')) {
+        let value = BASE32
+            .iter()
+            .position(|b| *b == c.to_ascii_uppercase())
+            .ok_or(TotpError::Base32)?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    if bytes.is_empty() {
+        return Err(TotpError::Empty);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totp(secret: &[u8], algorithm: Algorithm) -> Totp {
+        Totp {
+            secret: Zeroizing::new(secret.to_vec()),
+            algorithm,
+            digits: 8,
+            period: 30,
+        }
+    }
+
+    // RFC 6238 부록 B의 테스트 벡터
+    #[test]
+    fn rfc_6238_test_vectors() {
+        let sha1 = totp(b"12345678901234567890", Algorithm::Sha1);
+        let sha256 = totp(b"12345678901234567890123456789012", Algorithm::Sha256);
+        let sha512 = totp(
+            b"1234567890123456789012345678901234567890123456789012345678901234",
+            Algorithm::Sha512,
+        );
+        let vectors = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1111111111, "14050471", "67062674", "99943326"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (2000000000, "69279037", "90698825", "38618901"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+        for (time, code1, code256, code512) in vectors {
+            assert_eq!(sha1.code_at(time), code1);
+            assert_eq!(sha256.code_at(time), code256);
+            assert_eq!(sha512.code_at(time), code512);
+        }
+    }
+
+    #[test]
+    fn parses_secrets_and_uris() {
+        // "12345678901234567890"을 base32로 쓴 것
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        let plain = Totp::parse(&secret.to_lowercase()).unwrap();
+        assert_eq!(plain.secret.as_slice(), b"12345678901234567890");
+        assert_eq!(plain.code_at(59), "287082");
+
+        let uri = Totp::parse(&format!(
+            "otpauth://totp/Example:alice?secret={}&issuer=Example&digits=8&period=60&algorithm=SHA256",
+            secret
+        ))
+        .unwrap();
+        assert_eq!(uri.secret.as_slice(), b"12345678901234567890");
+        assert_eq!(uri.algorithm, Algorithm::Sha256);
+        assert_eq!((uri.digits, uri.period), (8, 60));
+
+        assert_eq!(Totp::parse("not base32!").unwrap_err(), TotpError::Base32);
+        assert!(matches!(
+            Totp::parse("otpauth://hotp/x?secret=GEZD&counter=1"),
+            Err(TotpError::Uri(_))
+        ));
+    }
+}
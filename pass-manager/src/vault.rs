@@ -0,0 +1,297 @@
+//! The encrypted vault file.
+//!
+//! The key is derived from the master password with Argon2id and the
+//! entries are sealed with XChaCha20-Poly1305. The file is a header followed
+//! by the ciphertext, and the header is authenticated along with it:
+//!
+//! ```text
+//! "PMVAULT1" | m_cost | t_cost | p_cost (u32 LE) | salt (16) | nonce (24) | ciphertext
+//! ```
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng, Payload};
+use chacha20poly1305::{Key, KeyInit, XChaCha20Poly1305, XNonce};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
+
+const MAGIC: &[u8; 8] = b"PMVAULT1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 12 + SALT_LEN + NONCE_LEN;
+// 헤더는 비밀번호를 확인하기 전에 읽으므로, 조작된 파일이 엄청난 메모리나
+// 시간을 쓰게 하지 못하도록 기본값의 4배까지만 받는다.
+const MAX_M_COST: u32 = Params::DEFAULT_M_COST * 4;
+const MAX_T_COST: u32 = Params::DEFAULT_T_COST * 4;
+const MAX_P_COST: u32 = Params::DEFAULT_P_COST * 4;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum VaultError {
+    #[error("wrong master password or damaged vault")]
+    WrongPassword,
+    #[error("not a vault file")]
+    Format,
+    #[error("master password is empty")]
+    EmptyPassword,
+    #[error("key derivation failed: {0}")]
+    Kdf(argon2::Error),
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid vault contents: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// An unlocked vault: where it lives and the key that opens it.
+pub struct Vault {
+    path: PathBuf,
+    params: Params,
+    salt: [u8; SALT_LEN],
+    key: Key,
+}
+
+impl Vault {
+    /// `vault.bin` in the user data directory.
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("pass-manager")
+            .join("vault.bin")
+    }
+
+    /// Creates a vault with a fresh salt. Nothing is written until [`Vault::save`].
+    pub fn create(path: &Path, master: &str) -> Result<Self, VaultError> {
+        Self::create_with(path, master, Params::default())
+    }
+
+    fn create_with(path: &Path, master: &str, params: Params) -> Result<Self, VaultError> {
+        if master.is_empty() {
+            return Err(VaultError::EmptyPassword);
+        }
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(master, &salt, &params)?;
+        Ok(Self {
+            path: path.to_owned(),
+            params,
+            salt,
+            key,
+        })
+    }
+
+    /// Opens the vault at `path` and decrypts its contents.
+    pub fn open<T: DeserializeOwned>(path: &Path, master: &str) -> Result<(Self, T), VaultError> {
+        let bytes = fs::read(path)?;
+        let (params, salt) = parse_header(&bytes)?;
+        let key = derive_key(master, &salt, &params)?;
+        let data = decrypt(&key, &bytes)?;
+
+        let vault = Self {
+            path: path.to_owned(),
+            params,
+            salt,
+            key,
+        };
+        Ok((vault, data))
+    }
+
+    /// Decrypts the file again with the key already derived, picking up
+    /// changes saved by other processes.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, VaultError> {
+        let bytes = fs::read(&self.path)?;
+        let (params, salt) = parse_header(&bytes)?;
+        // 다른 곳에서 금고를 새로 만들었다면 이 키로는 열 수 없다.
+        if salt != self.salt || params != self.params {
+            return Err(VaultError::WrongPassword);
+        }
+        decrypt(&self.key, &bytes)
+    }
+
+    /// Encrypts `data` with a new nonce and replaces the vault file.
+    pub fn save<T: Serialize>(&self, data: &T) -> Result<(), VaultError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(data)?);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&self.params.m_cost().to_le_bytes());
+        header.extend_from_slice(&self.params.t_cost().to_le_bytes());
+        header.extend_from_slice(&self.params.p_cost().to_le_bytes());
+        header.extend_from_slice(&self.salt);
+        header.extend_from_slice(&nonce);
+
+        let ciphertext = XChaCha20Poly1305::new(&self.key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: &header,
+                },
+            )
+            .map_err(|_| VaultError::WrongPassword)?;
+
+        let mut bytes = header;
+        bytes.extend_from_slice(&ciphertext);
+        write_private(&self.path, &bytes)?;
+        Ok(())
+    }
+
+    /// Whether `master` is the master password of this vault, to confirm
+    /// it again before something sensitive.
+    pub fn verify(&self, master: &str) -> bool {
+        match derive_key(master, &self.salt, &self.params) {
+            // 시간 차이로 키가 새지 않도록 모든 바이트를 비교한다.
+            Ok(key) => {
+                key.iter()
+                    .zip(self.key.iter())
+                    .fold(0, |diff, (a, b)| diff | (a ^ b))
+                    == 0
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Drop for Vault {
+    fn drop(&mut self) {
+        self.key.as_mut_slice().zeroize();
+    }
+}
+
+fn parse_header(bytes: &[u8]) -> Result<(Params, [u8; SALT_LEN]), VaultError> {
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(VaultError::Format);
+    }
+    let mut fields = bytes[MAGIC.len()..].chunks_exact(4).take(3).map(|chunk| {
+        let mut word = [0; 4];
+        word.copy_from_slice(chunk);
+        u32::from_le_bytes(word)
+    });
+    let (m_cost, t_cost, p_cost) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(m), Some(t), Some(p)) => (m, t, p),
+        _ => return Err(VaultError::Format),
+    };
+    if m_cost > MAX_M_COST || t_cost > MAX_T_COST || p_cost > MAX_P_COST {
+        return Err(VaultError::Format);
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|_| VaultError::Format)?;
+
+    let salt_at = MAGIC.len() + 12;
+    let mut salt = [0; SALT_LEN];
+    salt.copy_from_slice(&bytes[salt_at..salt_at + SALT_LEN]);
+    Ok((params, salt))
+}
+
+fn decrypt<T: DeserializeOwned>(key: &Key, bytes: &[u8]) -> Result<T, VaultError> {
+    let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+    let nonce = XNonce::from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+    let plaintext = XChaCha20Poly1305::new(key)
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| VaultError::WrongPassword)?;
+    let plaintext = Zeroizing::new(plaintext);
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+fn derive_key(master: &str, salt: &[u8], params: &Params) -> Result<Key, VaultError> {
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params.clone());
+    let mut key = Key::default();
+    argon2
+        .hash_password_into(master.as_bytes(), salt, &mut key)
+        .map_err(VaultError::Kdf)?;
+    Ok(key)
+}
+
+// 쓰는 도중에 죽어도 기존 파일이 남도록 임시 파일에 쓰고 rename한다.
+pub(crate) fn write_private(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    io::Write::write_all(&mut options.open(&tmp)?, bytes)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 테스트에서는 키 유도 비용을 낮춘다.
+    fn params() -> Params {
+        Params::new(64, 1, 1, None).unwrap()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pass-manager-{}-{}.bin", name, std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round-trip");
+        let vault = Vault::create_with(&path, "correct horse", params()).unwrap();
+        vault.save(&vec!["secret".to_owned()]).unwrap();
+
+        let (_, data): (_, Vec<String>) = Vault::open(&path, "correct horse").unwrap();
+        assert_eq!(data, vec!["secret"]);
+        vault.save(&vec!["changed".to_owned()]).unwrap();
+        assert_eq!(vault.load::<Vec<String>>().unwrap(), vec!["changed"]);
+        assert!(vault.verify("correct horse"));
+        assert!(!vault.verify("wrong horse"));
+        assert!(!fs::read(&path).unwrap().windows(6).any(|w| w == b"secret"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn wrong_password_and_tampering_are_rejected() {
+        let path = temp_path("wrong-password");
+        let vault = Vault::create_with(&path, "correct horse", params()).unwrap();
+        vault.save(&vec!["secret".to_owned()]).unwrap();
+
+        let result = Vault::open::<Vec<String>>(&path, "wrong horse");
+        assert!(matches!(result, Err(VaultError::WrongPassword)));
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&path, bytes).unwrap();
+        let result = Vault::open::<Vec<String>>(&path, "correct horse");
+        assert!(matches!(result, Err(VaultError::WrongPassword)));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn excessive_kdf_costs_are_rejected() {
+        let path = temp_path("costs");
+        let vault = Vault::create_with(&path, "correct horse", params()).unwrap();
+        vault.save(&vec!["secret".to_owned()]).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        let result = Vault::open::<Vec<String>>(&path, "correct horse");
+        assert!(matches!(result, Err(VaultError::Format)));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn empty_master_password_is_refused() {
+        let result = Vault::create_with(&temp_path("empty"), "", params());
+        assert!(matches!(result, Err(VaultError::EmptyPassword)));
+    }
+}